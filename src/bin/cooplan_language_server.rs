@@ -0,0 +1,58 @@
+use std::env;
+use std::io;
+use std::process;
+
+use cooplan_definition_schema_validator::cli::{load_definition, EXIT_FAILURE, EXIT_SUCCESS};
+use cooplan_definition_schema_validator::language_server::LanguageServer;
+use cooplan_definition_schema_validator::schema_validator::SchemaValidator;
+
+const USAGE: &str = "\
+Usage: cooplan-language-server --definition <FILE>
+
+Serves the Language Server Protocol over stdio for JSON files holding values of the
+definition read from a JSON or YAML file.";
+
+fn main() {
+    let mut definition: Option<String> = None;
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("-h" | "--help", _) => {
+                println!("{}", USAGE);
+                return;
+            }
+            ("-d" | "--definition", Some(value)) => definition = Some(value),
+            _ => fail(&format!("invalid argument '{}'", arg)),
+        }
+    }
+
+    let definition = match definition {
+        Some(definition) => definition,
+        None => fail("missing required option '--definition'"),
+    };
+
+    let definition = match load_definition(&definition) {
+        Ok(definition) => definition,
+        Err(error) => fail(&error.message),
+    };
+
+    let mut language_server = LanguageServer::new(definition, SchemaValidator::default());
+
+    if let Err(error) = language_server.serve(&mut io::stdin().lock(), &mut io::stdout().lock()) {
+        eprintln!("error: {}", error);
+        process::exit(EXIT_FAILURE);
+    }
+
+    // Exiting without a prior shutdown request is a failure of the client.
+    if language_server.is_shutdown_requested() {
+        process::exit(EXIT_SUCCESS);
+    }
+
+    process::exit(EXIT_FAILURE);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(EXIT_FAILURE);
+}
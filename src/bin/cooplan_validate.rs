@@ -0,0 +1,34 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process;
+
+use cooplan_definition_schema_validator::cli::{run, CliOptions, ColorMode, EXIT_FAILURE, USAGE};
+use cooplan_definition_schema_validator::schema_validator::SchemaValidator;
+
+fn main() {
+    let mut options = match CliOptions::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    if options.color == ColorMode::Auto {
+        options.color = if io::stdout().is_terminal() {
+            ColorMode::Always
+        } else {
+            ColorMode::Never
+        };
+    }
+
+    let exit_code = run(
+        &options,
+        &mut SchemaValidator::default(),
+        &mut io::stdin().lock(),
+        &mut io::stdout().lock(),
+        &mut io::stderr().lock(),
+    );
+
+    process::exit(exit_code);
+}
@@ -0,0 +1,71 @@
+use std::env;
+use std::process;
+
+use cooplan_definition_schema_validator::cli::EXIT_FAILURE;
+use cooplan_definition_schema_validator::http_service::{
+    load_definitions, serve, ValidationService,
+};
+use cooplan_definition_schema_validator::json_schema::JsonSchemaExporter;
+use cooplan_definition_schema_validator::schema_validator::SchemaValidator;
+
+const USAGE: &str = "\
+Usage: cooplan-validate-server --definitions <DIRECTORY> [--address <ADDRESS>]
+
+Serves validation over HTTP for the definitions of every JSON or YAML file of the
+directory, on 127.0.0.1:8080 by default.";
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+fn main() {
+    let mut definitions: Option<String> = None;
+    let mut address = DEFAULT_ADDRESS.to_string();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--definitions" | "--address" => args.next(),
+            _ => None,
+        };
+
+        match (arg.as_str(), value) {
+            ("--definitions", Some(value)) => definitions = Some(value),
+            ("--address", Some(value)) => address = value,
+            _ => fail(&format!("invalid argument '{}'", arg)),
+        }
+    }
+
+    let definitions = match definitions {
+        Some(definitions) => definitions,
+        None => fail("missing required option '--definitions'"),
+    };
+
+    let definition_registry = match load_definitions(&definitions) {
+        Ok(definition_registry) => definition_registry,
+        Err(error) => fail(&error.message),
+    };
+
+    let server = match tiny_http::Server::http(address.as_str()) {
+        Ok(server) => server,
+        Err(error) => fail(&format!("failed to listen on '{}': {}", address, error)),
+    };
+
+    eprintln!("listening on http://{}", address);
+
+    serve(
+        &server,
+        &mut ValidationService::new(
+            definition_registry,
+            SchemaValidator::default(),
+            JsonSchemaExporter::default(),
+        ),
+    );
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(EXIT_FAILURE);
+}
@@ -0,0 +1,102 @@
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+
+use crate::category_chain::{CategoryChain, CategoryIndex};
+use crate::error::{Error, ErrorKind};
+
+/// Collects the attributes of the category and of all its ancestors, starting
+/// with the category's own attributes.
+pub fn collect_from_definition_and_category(
+    definition: &Definition,
+    category_id: &String,
+) -> Result<Vec<ValidatedSourceAttribute>, Error> {
+    let category_index = CategoryIndex::new(definition);
+    let category_chain = category_index.build_chain(category_id)?;
+
+    collect_from_category_chain(&category_index, &category_chain)
+}
+
+/// Collects the attributes of the chain's categories, from the leaf to the root.
+///
+/// An attribute redeclared by a descendant category overrides its ancestor's
+/// declaration, so every attribute id is collected once. Overrides may only tighten
+/// the attribute, making it required, and fail when they change its data type or
+/// unit or make a required attribute optional.
+pub fn collect_from_category_chain(
+    category_index: &CategoryIndex,
+    category_chain: &CategoryChain,
+) -> Result<Vec<ValidatedSourceAttribute>, Error> {
+    let mut attributes: Vec<(&String, ValidatedSourceAttribute)> = Vec::new();
+
+    for category_id in category_chain.iter().rev() {
+        let category = match category_index.get(category_id) {
+            Some(category) => category,
+            None => continue,
+        };
+
+        for attribute in category.attributes.as_slice() {
+            let overriding_attribute = attributes
+                .iter()
+                .find(|(_, collected_attribute)| collected_attribute.id == attribute.id);
+
+            match overriding_attribute {
+                Some((overriding_category_id, overriding_attribute)) => check_override(
+                    overriding_category_id,
+                    overriding_attribute,
+                    category_id,
+                    attribute,
+                )?,
+                None => attributes.push((category_id, attribute.clone())),
+            }
+        }
+    }
+
+    Ok(attributes
+        .into_iter()
+        .map(|(_, attribute)| attribute)
+        .collect())
+}
+
+fn check_override(
+    overriding_category_id: &str,
+    overriding_attribute: &ValidatedSourceAttribute,
+    category_id: &str,
+    attribute: &ValidatedSourceAttribute,
+) -> Result<(), Error> {
+    if overriding_category_id == category_id {
+        return Err(Error::new(
+            ErrorKind::InvalidDefinition,
+            format!(
+                "attribute id '{}' is declared twice by category id '{}'",
+                attribute.id, category_id
+            ),
+        )
+        .with_attribute_id(attribute.id.clone()));
+    }
+
+    let conflict = if overriding_attribute.data_type != attribute.data_type {
+        format!(
+            "data type from '{}' to '{}'",
+            attribute.data_type, overriding_attribute.data_type
+        )
+    } else if overriding_attribute.unit != attribute.unit {
+        format!(
+            "unit from '{:?}' to '{:?}'",
+            attribute.unit, overriding_attribute.unit
+        )
+    } else if overriding_attribute.optional && !attribute.optional {
+        "requirement from required to optional".to_string()
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::new(
+        ErrorKind::InvalidDefinition,
+        format!(
+            "category id '{}' overrides attribute id '{}' of category id '{}' changing its {}",
+            overriding_category_id, attribute.id, category_id, conflict
+        ),
+    )
+    .with_attribute_id(attribute.id.clone()))
+}
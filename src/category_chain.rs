@@ -1,45 +1,178 @@
-use cooplan_definitions_lib::definition::Definition;
-use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-#[derive(Debug, Deserialize)]
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_category::ValidatedSourceCategory,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, ErrorKind};
+
+const BREADCRUMB_SEPARATOR: &str = " > ";
+
+/// Categories from the root, most generic, to the leaf, most specific.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryChain {
     category_chain: Vec<String>,
+    #[serde(default)]
+    category_names: Vec<String>,
 }
 
 impl CategoryChain {
     pub fn new(category_chain: Vec<String>) -> CategoryChain {
-        CategoryChain { category_chain }
+        CategoryChain {
+            category_chain,
+            category_names: Vec::new(),
+        }
+    }
+
+    /// Chain whose categories are named by `category_names`, in the same order.
+    pub fn new_with_names(
+        category_chain: Vec<String>,
+        category_names: Vec<String>,
+    ) -> CategoryChain {
+        CategoryChain {
+            category_chain,
+            category_names,
+        }
     }
 
     pub fn contains(&self, category: &String) -> bool {
         self.category_chain.contains(category)
     }
+
+    /// Category ids, from the root to the leaf.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.category_chain.iter()
+    }
+
+    /// Category names, from the root to the leaf, falling back to the id of the
+    /// categories without a known name.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.category_chain
+            .iter()
+            .enumerate()
+            .map(|(index, category)| self.category_names.get(index).unwrap_or(category))
+    }
+
+    /// First, most generic, category of the chain.
+    pub fn root(&self) -> Option<&String> {
+        self.category_chain.first()
+    }
+
+    /// Last, most specific, category of the chain.
+    pub fn leaf(&self) -> Option<&String> {
+        self.category_chain.last()
+    }
+
+    /// Amount of categories within the chain.
+    pub fn depth(&self) -> usize {
+        self.category_chain.len()
+    }
+
+    /// Whether the leaf is a strict descendant of the category.
+    pub fn is_descendant_of(&self, category: &String) -> bool {
+        match self.category_chain.split_last() {
+            Some((_, ancestors)) => ancestors.contains(category),
+            None => false,
+        }
+    }
 }
 
-pub fn build_from_definition_and_category(
-    definition: &Definition,
-    category: &String,
-) -> CategoryChain {
-    let mut category_chain = Vec::new();
+/// Chains are equal when their category ids are, names being informative only.
+impl PartialEq for CategoryChain {
+    fn eq(&self, other: &Self) -> bool {
+        self.category_chain == other.category_chain
+    }
+}
 
-    add_category_to_chain(definition, category, &mut category_chain);
+impl Eq for CategoryChain {}
 
-    CategoryChain::new(category_chain)
+/// Breadcrumb of the category names, e.g. `product > fruit`.
+impl fmt::Display for CategoryChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = self.names().map(String::as_str).collect();
+
+        write!(f, "{}", names.join(BREADCRUMB_SEPARATOR))
+    }
 }
 
-fn add_category_to_chain(
-    definition: &Definition,
-    category: &String,
-    category_chain: &mut Vec<String>,
-) {
-    for definition_category in definition.categories() {
-        if &definition_category.id == category {
-            if let Some(parent) = definition_category.parent {
-                add_category_to_chain(definition, &parent, category_chain);
+/// Categories of a definition, indexed by id.
+pub struct CategoryIndex {
+    categories: HashMap<String, ValidatedSourceCategory>,
+}
+
+impl CategoryIndex {
+    /// Indexes the definition's categories, keeping the first category of every id.
+    pub fn new(definition: &Definition) -> CategoryIndex {
+        let mut categories: HashMap<String, ValidatedSourceCategory> = HashMap::new();
+
+        for category in definition.categories() {
+            categories.entry(category.id.clone()).or_insert(category);
+        }
+
+        CategoryIndex { categories }
+    }
+
+    pub fn get(&self, category: &str) -> Option<&ValidatedSourceCategory> {
+        self.categories.get(category)
+    }
+
+    /// Builds the chain leading to the category, failing when the category is unknown
+    /// or when its ancestors cannot be resolved up to a root category.
+    pub fn build_chain(&self, category: &String) -> Result<CategoryChain, Error> {
+        let mut current_category = match self.categories.get(category) {
+            Some(current_category) => current_category,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidValue,
+                    format!("category id '{}' not found in definition", category),
+                ))
+            }
+        };
+
+        let mut category_chain = vec![current_category.id.clone()];
+        let mut category_names = vec![current_category.name.clone()];
+        let mut visited_categories: HashSet<&String> = HashSet::from([&current_category.id]);
+
+        while let Some(parent) = &current_category.parent {
+            if !visited_categories.insert(parent) {
+                return Err(Error::new(
+                    ErrorKind::InvalidDefinition,
+                    format!("category id '{}' has a cyclic parent chain", category),
+                ));
             }
 
-            category_chain.push(definition_category.id);
-            break;
+            current_category = match self.categories.get(parent) {
+                Some(parent_category) => parent_category,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidDefinition,
+                        format!(
+                            "category id '{}' has an unknown parent '{}'",
+                            current_category.id, parent
+                        ),
+                    ))
+                }
+            };
+
+            category_chain.push(current_category.id.clone());
+            category_names.push(current_category.name.clone());
         }
+
+        category_chain.reverse();
+        category_names.reverse();
+
+        Ok(CategoryChain::new_with_names(
+            category_chain,
+            category_names,
+        ))
     }
 }
+
+pub fn build_from_definition_and_category(
+    definition: &Definition,
+    category: &String,
+) -> Result<CategoryChain, Error> {
+    CategoryIndex::new(definition).build_chain(category)
+}
@@ -0,0 +1,485 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::mem;
+use std::path::Path;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::{json, Value};
+
+use crate::definition_registry::DefinitionRegistry;
+use crate::diagnostic::DiagnosticRenderer;
+use crate::error::{Error, ErrorKind};
+use crate::json_rpc::JsonRpcService;
+use crate::report::{to_junit_xml, to_sarif, ValidationOutcome, STDIN_SOURCE};
+use crate::schema_validator::SchemaValidator;
+
+pub const USAGE: &str = "\
+Usage: cooplan-validate --definition <FILE> [OPTIONS] [FILE...]
+       cooplan-validate --json-rpc [--definition <FILE>]
+
+Validates JSON values against a definition read from a JSON or YAML file.
+Values are read from the given files, or from stdin when no file or '-' is given.
+
+Options:
+  -d, --definition <FILE>  Definition file, YAML requires the 'yaml' feature
+  -f, --format <FORMAT>    Output format: 'human' (default), 'pretty', 'json', 'junit' or 'sarif'
+      --color <WHEN>       Color pretty output: 'auto' (default), 'always' or 'never'
+      --ndjson             Read one value per line, implied by '.ndjson' and '.jsonl' files
+      --json-rpc           Answer JSON-RPC requests, one per line, from stdin until shutdown
+  -h, --help               Print this help
+
+Exit codes: 0 when every value is valid, 1 when a value is invalid, 2 on usage,
+definition or read failures.";
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_INVALID: i32 = 1;
+pub const EXIT_FAILURE: i32 = 2;
+
+const STDIN_INPUT: &str = "-";
+const TOOL_NAME: &str = "cooplan-validate";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    /// Compiler-style diagnostic of every invalid value, showing its source.
+    Pretty,
+    /// One JSON object per validated value, followed by a summary object.
+    Json,
+    /// JUnit XML document, one test case per validated value.
+    Junit,
+    /// SARIF log, one result per invalid value.
+    Sarif,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Colors when the output is a terminal, which the binary resolves to `Always`
+    /// or `Never`. `run` does not color it.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CliOptions {
+    /// Definition file, empty when serving JSON-RPC without a preloaded definition.
+    pub definition: String,
+    pub inputs: Vec<String>,
+    pub format: OutputFormat,
+    pub color: ColorMode,
+    pub ndjson: bool,
+    pub json_rpc: bool,
+    pub help: bool,
+}
+
+impl CliOptions {
+    /// Parses the arguments, without the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<CliOptions, Error> {
+        let mut options = CliOptions::default();
+        let mut definition: Option<String> = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--ndjson" => options.ndjson = true,
+                "--json-rpc" => options.json_rpc = true,
+                "-d" | "--definition" => definition = Some(next_value(&mut args, &arg)?),
+                "-f" | "--format" => {
+                    options.format = match next_value(&mut args, &arg)?.as_str() {
+                        "human" => OutputFormat::Human,
+                        "pretty" => OutputFormat::Pretty,
+                        "json" => OutputFormat::Json,
+                        "junit" => OutputFormat::Junit,
+                        "sarif" => OutputFormat::Sarif,
+                        format => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidArgument,
+                                format!("unknown output format '{}'", format),
+                            ))
+                        }
+                    }
+                }
+                "--color" => {
+                    options.color = match next_value(&mut args, &arg)?.as_str() {
+                        "auto" => ColorMode::Auto,
+                        "always" => ColorMode::Always,
+                        "never" => ColorMode::Never,
+                        color => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidArgument,
+                                format!("unknown color mode '{}'", color),
+                            ))
+                        }
+                    }
+                }
+                _ if arg.starts_with('-') && arg != STDIN_INPUT => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidArgument,
+                        format!("unknown option '{}'", arg),
+                    ))
+                }
+                _ => options.inputs.push(arg),
+            }
+        }
+
+        if options.help {
+            return Ok(options);
+        }
+
+        options.definition = match definition {
+            Some(definition) => definition,
+            None if options.json_rpc => String::new(),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidArgument,
+                    "missing required option '--definition'",
+                ))
+            }
+        };
+
+        if options.inputs.is_empty() {
+            options.inputs.push(STDIN_INPUT.to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String, Error> {
+    match args.next() {
+        Some(value) => Ok(value),
+        None => Err(Error::new(
+            ErrorKind::InvalidArgument,
+            format!("option '{}' requires a value", option),
+        )),
+    }
+}
+
+/// Reads the definition, as YAML when the file's extension is `.yaml` or `.yml` and
+/// as JSON otherwise.
+pub fn load_definition(path: &str) -> Result<Definition, Error> {
+    let content = read_file(path)?;
+
+    if has_extension(path, &["yaml", "yml"]) {
+        return parse_yaml_definition(path, &content);
+    }
+
+    match serde_json::from_str(&content) {
+        Ok(definition) => Ok(definition),
+        Err(error) => Err(Error::new(
+            ErrorKind::DeserializationFailure,
+            format!("failed to deserialize definition '{}': {}", path, error),
+        )),
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml_definition(path: &str, content: &str) -> Result<Definition, Error> {
+    match serde_yaml::from_str(content) {
+        Ok(definition) => Ok(definition),
+        Err(error) => Err(Error::new(
+            ErrorKind::DeserializationFailure,
+            format!("failed to deserialize definition '{}': {}", path, error),
+        )),
+    }
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml_definition(path: &str, _content: &str) -> Result<Definition, Error> {
+    Err(Error::new(
+        ErrorKind::InvalidArgument,
+        format!(
+            "cannot read YAML definition '{}', the 'yaml' feature is disabled",
+            path
+        ),
+    ))
+}
+
+/// Validates every input, writing one result per value to `output` and failures
+/// preventing validation to `error_output`, and returns the process' exit code.
+pub fn run(
+    options: &CliOptions,
+    schema_validator: &mut SchemaValidator,
+    stdin: &mut dyn BufRead,
+    output: &mut dyn Write,
+    error_output: &mut dyn Write,
+) -> i32 {
+    if options.help {
+        return write_or_fail(writeln!(output, "{}", USAGE));
+    }
+
+    if options.json_rpc {
+        return serve_json_rpc(options, schema_validator, stdin, output, error_output);
+    }
+
+    let definition = match load_definition(&options.definition) {
+        Ok(definition) => definition,
+        Err(error) => return report_failure(error_output, &error),
+    };
+
+    let mut diagnostic_renderer = DiagnosticRenderer::default();
+    diagnostic_renderer.set_colored(options.color == ColorMode::Always);
+
+    let mut value_validation = ValueValidation {
+        options,
+        schema_validator,
+        definition: &definition,
+        diagnostic_renderer: &diagnostic_renderer,
+        output,
+        validated: 0,
+        invalid: 0,
+        outcomes: Vec::new(),
+    };
+
+    for input in options.inputs.as_slice() {
+        let mut file_reader;
+
+        let (reader, source): (&mut dyn BufRead, &str) = if input == STDIN_INPUT {
+            (&mut *stdin, STDIN_SOURCE)
+        } else {
+            match File::open(input) {
+                Ok(file) => {
+                    file_reader = BufReader::new(file);
+
+                    (&mut file_reader, input)
+                }
+                Err(error) => return report_failure(error_output, &read_error(input, error)),
+            }
+        };
+
+        if options.ndjson || has_extension(input, &["ndjson", "jsonl"]) {
+            for (index, line) in reader.lines().enumerate() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(error) => return report_failure(error_output, &read_error(source, error)),
+                };
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                if value_validation
+                    .validate(source, Some(index + 1), line)
+                    .is_err()
+                {
+                    return EXIT_FAILURE;
+                }
+            }
+        } else {
+            let mut content = String::new();
+
+            if let Err(error) = reader.read_to_string(&mut content) {
+                return report_failure(error_output, &read_error(source, error));
+            }
+
+            if value_validation.validate(source, None, content).is_err() {
+                return EXIT_FAILURE;
+            }
+        }
+    }
+
+    if value_validation.write_summary().is_err() {
+        return EXIT_FAILURE;
+    }
+
+    if value_validation.invalid == 0 {
+        EXIT_SUCCESS
+    } else {
+        EXIT_INVALID
+    }
+}
+
+/// Validates the values of the inputs one by one, writing every outcome as soon as
+/// it is known. Outcomes are only kept for the formats writing a whole document, so
+/// that streams of values are validated in constant memory otherwise.
+struct ValueValidation<'a> {
+    options: &'a CliOptions,
+    schema_validator: &'a mut SchemaValidator,
+    definition: &'a Definition,
+    diagnostic_renderer: &'a DiagnosticRenderer,
+    output: &'a mut dyn Write,
+    validated: usize,
+    invalid: usize,
+    outcomes: Vec<ValidationOutcome>,
+}
+
+impl<'a> ValueValidation<'a> {
+    fn validate(
+        &mut self,
+        source: &str,
+        line: Option<usize>,
+        content: String,
+    ) -> std::io::Result<()> {
+        let result = self
+            .schema_validator
+            .validate(content.clone(), self.definition.clone());
+        let outcome = ValidationOutcome::new(source, line, result);
+        let diagnostic = match self.options.format {
+            OutputFormat::Pretty => {
+                self.diagnostic_renderer
+                    .render(&outcome, &content, self.definition)
+            }
+            _ => None,
+        };
+
+        write_outcome(self.output, self.options.format, &outcome, diagnostic)?;
+
+        self.validated += 1;
+
+        if !outcome.is_valid() {
+            self.invalid += 1;
+        }
+
+        if matches!(
+            self.options.format,
+            OutputFormat::Junit | OutputFormat::Sarif
+        ) {
+            self.outcomes.push(outcome);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the summary of the streaming formats, or the whole document of the others.
+    fn write_summary(&mut self) -> std::io::Result<()> {
+        match self.options.format {
+            OutputFormat::Human | OutputFormat::Pretty => writeln!(
+                self.output,
+                "{} values validated, {} invalid",
+                self.validated, self.invalid
+            ),
+            OutputFormat::Json => {
+                let summary: Value =
+                    json!({ "valid": self.validated - self.invalid, "invalid": self.invalid });
+
+                writeln!(self.output, "{}", json!({ "summary": summary }))
+            }
+            OutputFormat::Junit => {
+                write!(self.output, "{}", to_junit_xml(TOOL_NAME, &self.outcomes))
+            }
+            OutputFormat::Sarif => {
+                writeln!(self.output, "{:#}", to_sarif(TOOL_NAME, &self.outcomes))
+            }
+        }
+    }
+}
+
+fn serve_json_rpc(
+    options: &CliOptions,
+    schema_validator: &mut SchemaValidator,
+    stdin: &mut dyn BufRead,
+    output: &mut dyn Write,
+    error_output: &mut dyn Write,
+) -> i32 {
+    let mut definition_registry = DefinitionRegistry::default();
+
+    if !options.definition.is_empty() {
+        match load_definition(&options.definition) {
+            Ok(definition) => definition_registry.register_definition(definition),
+            Err(error) => return report_failure(error_output, &error),
+        };
+    }
+
+    let mut json_rpc_service =
+        JsonRpcService::new(definition_registry, mem::take(schema_validator));
+
+    match json_rpc_service.serve(stdin, output) {
+        Ok(_) => EXIT_SUCCESS,
+        Err(error) => report_failure(error_output, &error),
+    }
+}
+
+/// Writes the outcome as soon as it is known, for the formats streaming their results,
+/// the pretty format writing the outcome's rendered diagnostic.
+fn write_outcome(
+    output: &mut dyn Write,
+    format: OutputFormat,
+    outcome: &ValidationOutcome,
+    diagnostic: Option<String>,
+) -> std::io::Result<()> {
+    match (&outcome.error, format) {
+        (None, OutputFormat::Human) | (None, OutputFormat::Pretty) => {
+            writeln!(output, "{}: ok", outcome.location())
+        }
+        (Some(_), OutputFormat::Pretty) => writeln!(output, "{}", diagnostic.unwrap_or_default()),
+        (Some(error), OutputFormat::Human) => match &error.attribute_id {
+            Some(attribute_id) => writeln!(
+                output,
+                "{}: {:?} (attribute '{}'): {}",
+                outcome.error_location(),
+                error.kind(),
+                attribute_id,
+                error
+            ),
+            None => writeln!(
+                output,
+                "{}: {:?}: {}",
+                outcome.error_location(),
+                error.kind(),
+                error
+            ),
+        },
+        (None, OutputFormat::Json) => writeln!(
+            output,
+            "{}",
+            json!({ "source": outcome.location(), "valid": true })
+        ),
+        (Some(error), OutputFormat::Json) => writeln!(
+            output,
+            "{}",
+            json!({
+                "source": outcome.location(),
+                "valid": false,
+                "kind": format!("{:?}", error.kind()),
+                "message": error.message,
+                "attribute_id": error.attribute_id,
+                "line": outcome.error_position().map(|(line, _)| line),
+                "column": outcome.error_position().map(|(_, column)| column),
+            })
+        ),
+        (_, OutputFormat::Junit) | (_, OutputFormat::Sarif) => Ok(()),
+    }
+}
+
+fn read_file(path: &str) -> Result<String, Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(error) => Err(read_error(path, error)),
+    }
+}
+
+fn read_error(source: &str, error: std::io::Error) -> Error {
+    Error::new(
+        ErrorKind::IoFailure,
+        format!("failed to read '{}': {}", source, error),
+    )
+}
+
+/// Whether the path's extension is one of the extensions, ignoring ASCII case.
+pub(crate) fn has_extension(path: &str, extensions: &[&str]) -> bool {
+    match Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some(extension) => extensions
+            .iter()
+            .any(|candidate| extension.eq_ignore_ascii_case(candidate)),
+        None => false,
+    }
+}
+
+fn report_failure(error_output: &mut dyn Write, error: &Error) -> i32 {
+    let _ = writeln!(error_output, "error: {}", error);
+
+    EXIT_FAILURE
+}
+
+fn write_or_fail(result: std::io::Result<()>) -> i32 {
+    match result {
+        Ok(_) => EXIT_SUCCESS,
+        Err(_) => EXIT_FAILURE,
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+    validated_source_category::ValidatedSourceCategory,
+};
+
+use crate::category_attributes::collect_from_category_chain;
+use crate::category_chain::CategoryIndex;
+use crate::error::{Error, ErrorKind};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords which cannot be used as raw identifiers either.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+/// Generates Rust structs, mirroring the selectable categories of a definition,
+/// meant to be written into a file from a `build.rs` script or a CLI.
+pub struct StructGenerator {
+    rust_types: HashMap<String, String>,
+}
+
+impl StructGenerator {
+    fn initialize_base_rust_types(rust_types: &mut HashMap<String, String>) {
+        rust_types.insert("string".to_string(), "String".to_string());
+        rust_types.insert("integer".to_string(), "i64".to_string());
+        rust_types.insert("decimal".to_string(), "f64".to_string());
+        rust_types.insert("boolean".to_string(), "bool".to_string());
+    }
+
+    /// Registers the Rust type used for attributes of a custom data type. The type
+    /// must implement `serde::Serialize` and `serde::Deserialize`.
+    pub fn register_rust_type(&mut self, data_type: String, rust_type: String) {
+        self.rust_types.insert(data_type, rust_type);
+    }
+
+    /// Generates a struct, alongside its `TryFrom<DefinitionValue>` implementation,
+    /// for every category which is selectable as last.
+    pub fn generate(&self, definition: &Definition) -> Result<String, Error> {
+        let mut code = String::new();
+        let mut struct_names: HashSet<String> = HashSet::new();
+        let category_index = CategoryIndex::new(definition);
+
+        let _ = writeln!(
+            code,
+            "// Generated from definition version '{}'. Do not edit by hand.",
+            definition.version()
+        );
+
+        for category in definition.categories() {
+            if !category.selectable_as_last {
+                continue;
+            }
+
+            let struct_name = unique_identifier(
+                &mut struct_names,
+                to_upper_camel_case(&category.name),
+                to_upper_camel_case(&category.id),
+                "",
+            );
+
+            let category_chain = category_index.build_chain(&category.id)?;
+            let attributes = collect_from_category_chain(&category_index, &category_chain)?;
+
+            self.write_struct(&mut code, &category, &struct_name, &attributes)?;
+            write_try_from(&mut code, &category, &struct_name);
+        }
+
+        Ok(code)
+    }
+
+    fn write_struct(
+        &self,
+        code: &mut String,
+        category: &ValidatedSourceCategory,
+        struct_name: &str,
+        attributes: &[ValidatedSourceAttribute],
+    ) -> Result<(), Error> {
+        let mut field_names: HashSet<String> = HashSet::new();
+
+        let _ = writeln!(code);
+        let _ = writeln!(code, "/// {} (category '{}').", category.name, category.id);
+        let _ = writeln!(
+            code,
+            "#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]"
+        );
+        let _ = writeln!(code, "pub struct {} {{", struct_name);
+
+        for attribute in attributes {
+            let rust_type = match self.rust_types.get(&attribute.data_type) {
+                Some(rust_type) => rust_type,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::DataTypeNotRegistered,
+                        format!("no rust type found for data type '{}'", attribute.data_type),
+                    ))
+                }
+            };
+
+            let field_name = unique_identifier(
+                &mut field_names,
+                to_field_name(attribute),
+                to_snake_case(&attribute.id),
+                "_",
+            );
+
+            if let Some(unit) = &attribute.unit {
+                let _ = writeln!(code, "    /// {} ({}).", attribute.name, unit);
+            } else {
+                let _ = writeln!(code, "    /// {}.", attribute.name);
+            }
+
+            if attribute.optional {
+                let _ = writeln!(
+                    code,
+                    "    #[serde(rename = {:?}, default, skip_serializing_if = \"Option::is_none\")]",
+                    attribute.id
+                );
+                let _ = writeln!(code, "    pub {}: Option<{}>,", field_name, rust_type);
+            } else {
+                let _ = writeln!(code, "    #[serde(rename = {:?})]", attribute.id);
+                let _ = writeln!(code, "    pub {}: {},", field_name, rust_type);
+            }
+        }
+
+        let _ = writeln!(code, "}}");
+
+        Ok(())
+    }
+}
+
+impl Default for StructGenerator {
+    fn default() -> Self {
+        let mut rust_types: HashMap<String, String> = HashMap::new();
+
+        StructGenerator::initialize_base_rust_types(&mut rust_types);
+
+        StructGenerator { rust_types }
+    }
+}
+
+fn write_try_from(code: &mut String, category: &ValidatedSourceCategory, struct_name: &str) {
+    let _ = write!(
+        code,
+        r#"
+impl TryFrom<cooplan_definition_schema_validator::definition_value::DefinitionValue> for {struct_name} {{
+    type Error = cooplan_definition_schema_validator::error::Error;
+
+    fn try_from(
+        value: cooplan_definition_schema_validator::definition_value::DefinitionValue,
+    ) -> Result<Self, Self::Error> {{
+        if value.category_chain().leaf().map(String::as_str) != Some({category_id:?}) {{
+            return Err(cooplan_definition_schema_validator::error::Error::new(
+                cooplan_definition_schema_validator::error::ErrorKind::ValueDefinitionMismatch,
+                format!("value does not belong to category '{{}}'", {category_id:?}),
+            ));
+        }}
+
+        match serde_json::from_value(serde_json::Value::Object(value.value().clone())) {{
+            Ok(typed_value) => Ok(typed_value),
+            Err(error) => Err(cooplan_definition_schema_validator::error::Error::new(
+                cooplan_definition_schema_validator::error::ErrorKind::DeserializationFailure,
+                format!("failed to convert value into '{struct_name}': {{}}", error),
+            )),
+        }}
+    }}
+}}
+"#,
+        struct_name = struct_name,
+        category_id = category.id,
+    );
+}
+
+/// Snake case attribute name, falling back to its id for names without any ASCII
+/// alphanumeric character.
+fn to_field_name(attribute: &ValidatedSourceAttribute) -> String {
+    let field_name = to_snake_case(&attribute.name);
+
+    if field_name.trim_matches('_').is_empty() {
+        format!(
+            "attribute_{}",
+            to_snake_case(&attribute.id).trim_start_matches('_')
+        )
+    } else {
+        field_name
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::new();
+    let mut previous_is_lowercase = false;
+
+    for character in name.chars() {
+        if character.is_ascii_alphanumeric() {
+            if character.is_ascii_uppercase() && previous_is_lowercase {
+                snake_case.push('_');
+            }
+
+            previous_is_lowercase = character.is_ascii_lowercase() || character.is_ascii_digit();
+            snake_case.push(character.to_ascii_lowercase());
+        } else {
+            if !snake_case.ends_with('_') {
+                snake_case.push('_');
+            }
+
+            previous_is_lowercase = false;
+        }
+    }
+
+    let snake_case = snake_case.trim_matches('_').to_string();
+
+    if snake_case.is_empty() || snake_case.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", snake_case)
+    } else {
+        snake_case
+    }
+}
+
+fn to_upper_camel_case(name: &str) -> String {
+    let mut upper_camel_case = String::new();
+
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut characters = word.chars();
+
+        if let Some(first) = characters.next() {
+            upper_camel_case.push(first.to_ascii_uppercase());
+            upper_camel_case.push_str(characters.as_str());
+        }
+    }
+
+    if upper_camel_case.is_empty() || upper_camel_case.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Category{}", upper_camel_case)
+    } else {
+        upper_camel_case
+    }
+}
+
+/// Identifier not taken yet, suffixed with the fallback, and then with a counter,
+/// on collision.
+fn unique_identifier(
+    identifiers: &mut HashSet<String>,
+    identifier: String,
+    fallback: String,
+    separator: &str,
+) -> String {
+    let mut unique_identifier = escape_keyword(identifier.clone());
+    let mut counter = 1;
+
+    while identifiers.contains(&unique_identifier) {
+        unique_identifier = if counter == 1 {
+            format!("{}{}{}", identifier, separator, fallback)
+        } else {
+            format!(
+                "{}{}{}{}{}",
+                identifier, separator, fallback, separator, counter
+            )
+        };
+        counter += 1;
+    }
+
+    identifiers.insert(unique_identifier.clone());
+
+    unique_identifier
+}
+
+fn escape_keyword(identifier: String) -> String {
+    if NON_RAW_KEYWORDS.contains(&identifier.as_str()) {
+        format!("{}_", identifier)
+    } else if RUST_KEYWORDS.contains(&identifier.as_str()) {
+        format!("r#{}", identifier)
+    } else {
+        identifier
+    }
+}
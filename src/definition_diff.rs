@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+
+use crate::category_attributes::collect_from_category_chain;
+use crate::category_chain::CategoryIndex;
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefinitionChangeKind {
+    CategoryAdded,
+    CategoryRemoved,
+    CategoryReparented {
+        old_parent: Option<String>,
+        new_parent: Option<String>,
+    },
+    SelectableAsLastChanged {
+        old_selectable_as_last: bool,
+        new_selectable_as_last: bool,
+    },
+    AttributeAdded {
+        attribute_id: String,
+        optional: bool,
+    },
+    AttributeRemoved {
+        attribute_id: String,
+    },
+    AttributeRetyped {
+        attribute_id: String,
+        old_data_type: String,
+        new_data_type: String,
+    },
+    AttributeOptionalChanged {
+        attribute_id: String,
+        old_optional: bool,
+        new_optional: bool,
+    },
+}
+
+/// Change of a category, including the attributes it inherits from its parents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefinitionChange {
+    pub category_id: String,
+    pub kind: DefinitionChangeKind,
+    /// Whether values valid for the old definition may be invalid for the new one.
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DefinitionDiff {
+    pub changes: Vec<DefinitionChange>,
+}
+
+impl DefinitionDiff {
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &DefinitionChange> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+}
+
+/// Compares both definitions category by category, resolving the attributes of each
+/// category through its parent chain the same way `SchemaValidator` does.
+pub fn diff(
+    old_definition: &Definition,
+    new_definition: &Definition,
+) -> Result<DefinitionDiff, Error> {
+    let old_categories = old_definition.categories();
+    let old_category_ids: HashSet<&String> =
+        old_categories.iter().map(|category| &category.id).collect();
+
+    let old_category_index = CategoryIndex::new(old_definition);
+    let new_category_index = CategoryIndex::new(new_definition);
+
+    let mut definition_diff = DefinitionDiff::default();
+
+    for old_category in old_categories.iter() {
+        let new_category = match new_category_index.get(&old_category.id) {
+            Some(new_category) => new_category,
+            None => {
+                definition_diff.changes.push(DefinitionChange {
+                    category_id: old_category.id.clone(),
+                    kind: DefinitionChangeKind::CategoryRemoved,
+                    breaking: true,
+                });
+                continue;
+            }
+        };
+
+        if old_category.parent != new_category.parent {
+            definition_diff.changes.push(DefinitionChange {
+                category_id: old_category.id.clone(),
+                kind: DefinitionChangeKind::CategoryReparented {
+                    old_parent: old_category.parent.clone(),
+                    new_parent: new_category.parent.clone(),
+                },
+                breaking: true,
+            });
+        }
+
+        if old_category.selectable_as_last != new_category.selectable_as_last {
+            definition_diff.changes.push(DefinitionChange {
+                category_id: old_category.id.clone(),
+                kind: DefinitionChangeKind::SelectableAsLastChanged {
+                    old_selectable_as_last: old_category.selectable_as_last,
+                    new_selectable_as_last: new_category.selectable_as_last,
+                },
+                breaking: old_category.selectable_as_last,
+            });
+        }
+
+        let old_attributes = collect_from_category_chain(
+            &old_category_index,
+            &old_category_index.build_chain(&old_category.id)?,
+        )?;
+        let new_attributes = collect_from_category_chain(
+            &new_category_index,
+            &new_category_index.build_chain(&new_category.id)?,
+        )?;
+
+        diff_attributes(
+            &mut definition_diff,
+            &old_category.id,
+            &old_attributes,
+            &new_attributes,
+        );
+    }
+
+    for new_category in new_definition.categories() {
+        if !old_category_ids.contains(&new_category.id) {
+            definition_diff.changes.push(DefinitionChange {
+                category_id: new_category.id.clone(),
+                kind: DefinitionChangeKind::CategoryAdded,
+                breaking: false,
+            });
+        }
+    }
+
+    Ok(definition_diff)
+}
+
+fn diff_attributes(
+    definition_diff: &mut DefinitionDiff,
+    category_id: &str,
+    old_attributes: &[ValidatedSourceAttribute],
+    new_attributes: &[ValidatedSourceAttribute],
+) {
+    for old_attribute in old_attributes {
+        let new_attribute = match new_attributes
+            .iter()
+            .find(|new_attribute| new_attribute.id == old_attribute.id)
+        {
+            Some(new_attribute) => new_attribute,
+            None => {
+                // Attributes unknown to the category are ignored by the validation.
+                definition_diff.changes.push(DefinitionChange {
+                    category_id: category_id.to_string(),
+                    kind: DefinitionChangeKind::AttributeRemoved {
+                        attribute_id: old_attribute.id.clone(),
+                    },
+                    breaking: false,
+                });
+                continue;
+            }
+        };
+
+        if old_attribute.data_type != new_attribute.data_type {
+            definition_diff.changes.push(DefinitionChange {
+                category_id: category_id.to_string(),
+                kind: DefinitionChangeKind::AttributeRetyped {
+                    attribute_id: old_attribute.id.clone(),
+                    old_data_type: old_attribute.data_type.clone(),
+                    new_data_type: new_attribute.data_type.clone(),
+                },
+                breaking: true,
+            });
+        }
+
+        if old_attribute.optional != new_attribute.optional {
+            definition_diff.changes.push(DefinitionChange {
+                category_id: category_id.to_string(),
+                kind: DefinitionChangeKind::AttributeOptionalChanged {
+                    attribute_id: old_attribute.id.clone(),
+                    old_optional: old_attribute.optional,
+                    new_optional: new_attribute.optional,
+                },
+                breaking: old_attribute.optional,
+            });
+        }
+    }
+
+    for new_attribute in new_attributes {
+        if !old_attributes
+            .iter()
+            .any(|old_attribute| old_attribute.id == new_attribute.id)
+        {
+            definition_diff.changes.push(DefinitionChange {
+                category_id: category_id.to_string(),
+                kind: DefinitionChangeKind::AttributeAdded {
+                    attribute_id: new_attribute.id.clone(),
+                    optional: new_attribute.optional,
+                },
+                breaking: !new_attribute.optional,
+            });
+        }
+    }
+}
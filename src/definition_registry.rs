@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::{Map, Value};
+
+use crate::definition_value::DefinitionValue;
+use crate::error::{Error, ErrorKind};
+use crate::schema_validator::{SchemaValidator, VALUE_VERSION};
+use crate::source_map::{locate_member, SourcePosition, SourceSpan};
+use crate::version_policy::VersionPolicy;
+
+/// Definition used when a value's version has not been registered.
+///
+/// The fallback definition's version still has to be accepted by the
+/// `SchemaValidator`'s version policy, so that, under [`VersionPolicy::Exact`],
+/// values of unknown versions are rejected whatever the fallback policy.
+#[derive(Debug, Clone, Default)]
+pub enum FallbackPolicy {
+    /// Values of unknown versions are rejected.
+    #[default]
+    Reject,
+    /// Values of unknown versions are validated by the last registered definition.
+    LatestRegistered,
+    /// Values of unknown versions are validated by the definition of this version.
+    Version(String),
+}
+
+/// Definitions of many versions, picked by the version the value declares.
+#[derive(Debug, Default)]
+pub struct DefinitionRegistry {
+    definitions: HashMap<String, Definition>,
+    /// Registered versions, from the first registered to the last.
+    registration_order: Vec<String>,
+    fallback_policy: FallbackPolicy,
+}
+
+impl DefinitionRegistry {
+    /// Registers the definition, replacing and returning the one of the same version.
+    pub fn register_definition(&mut self, definition: Definition) -> Option<Definition> {
+        let version = definition.version().to_lowercase();
+        self.registration_order
+            .retain(|registered_version| registered_version != &version);
+        self.registration_order.push(version.clone());
+
+        self.definitions.insert(version, definition)
+    }
+
+    /// Removes the definition of the version, the previously registered definition
+    /// becoming the last registered one when it was.
+    pub fn remove_definition(&mut self, version: &str) -> Option<Definition> {
+        let version = version.to_lowercase();
+        self.registration_order
+            .retain(|registered_version| registered_version != &version);
+
+        self.definitions.remove(&version)
+    }
+
+    pub fn set_fallback_policy(&mut self, fallback_policy: FallbackPolicy) {
+        self.fallback_policy = fallback_policy;
+    }
+
+    /// Registered definition of the version, ignoring case.
+    pub fn get(&self, version: &str) -> Option<&Definition> {
+        self.definitions.get(&version.to_lowercase())
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.values()
+    }
+
+    /// Definition validating values of the version, applying the fallback policy.
+    pub fn resolve(&self, version: &str) -> Result<&Definition, Error> {
+        if let Some(definition) = self.get(version) {
+            return Ok(definition);
+        }
+
+        let fallback_definition = match &self.fallback_policy {
+            FallbackPolicy::Reject => None,
+            FallbackPolicy::LatestRegistered => self
+                .registration_order
+                .last()
+                .and_then(|latest_version| self.definitions.get(latest_version)),
+            FallbackPolicy::Version(fallback_version) => self.get(fallback_version),
+        };
+
+        match fallback_definition {
+            Some(definition) => Ok(definition),
+            None => Err(Error::new(
+                ErrorKind::UnknownDefinitionVersion,
+                format!("no definition registered for version '{}'", version),
+            )),
+        }
+    }
+
+    /// Definition validating values of the version with the schema validator, the
+    /// fallback definition being rejected when its version policy is exact.
+    fn resolve_for(
+        &self,
+        schema_validator: &SchemaValidator,
+        version: &str,
+    ) -> Result<&Definition, Error> {
+        let definition = self.resolve(version)?;
+
+        if self.get(version).is_none()
+            && matches!(schema_validator.version_policy(), VersionPolicy::Exact)
+        {
+            return Err(Error::new(
+                ErrorKind::UnknownDefinitionVersion,
+                format!(
+                    "no definition registered for version '{}', the fallback definition's version '{}' not being accepted by the exact version policy",
+                    version,
+                    definition.version()
+                ),
+            ));
+        }
+
+        Ok(definition)
+    }
+
+    pub fn validate(
+        &self,
+        schema_validator: &mut SchemaValidator,
+        value: String,
+    ) -> Result<DefinitionValue, Error> {
+        let object: Map<String, Value> = match serde_json::from_str(value.as_str()) {
+            Ok(object) => object,
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::DeserializationFailure,
+                    format!("failed to deserialize value: {}", error),
+                )
+                .with_span(SourceSpan::at(SourcePosition::of_json_error(
+                    value.as_str(),
+                    &error,
+                ))))
+            }
+        };
+
+        self.validate_located(schema_validator, object, Some(value.as_str()))
+    }
+
+    /// Validates the value against the definition of the version it declares.
+    pub fn validate_object(
+        &self,
+        schema_validator: &mut SchemaValidator,
+        object: Map<String, Value>,
+    ) -> Result<DefinitionValue, Error> {
+        self.validate_located(schema_validator, object, None)
+    }
+
+    fn validate_located(
+        &self,
+        schema_validator: &mut SchemaValidator,
+        object: Map<String, Value>,
+        source: Option<&str>,
+    ) -> Result<DefinitionValue, Error> {
+        let definition = schema_validator
+            .try_get_version(&object)
+            .and_then(|version| self.resolve_for(schema_validator, &version))
+            .map_err(|error| locate_member(error, source, VALUE_VERSION))?
+            .clone();
+
+        schema_validator.validate_located(object, definition, source)
+    }
+}
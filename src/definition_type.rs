@@ -1,18 +1,276 @@
-use serde::Deserialize;
+use std::collections::HashMap;
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+use cooplan_definitions_lib::definition::Definition;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::category_chain::CategoryChain;
+use crate::error::{Error, ErrorKind};
+
+pub const PRODUCT_ATTRIBUTE_ID: &str = "4ed908eb-50b6-4faa-9baa-a7a897cec30f";
+pub const MODIFIER_ATTRIBUTE_ID: &str = "d22202c1-44cb-471f-9c69-07f7eea1b9bf";
+pub const SERVICE_ATTRIBUTE_ID: &str = "70b0d023-20e6-45cb-9654-e5fd42749642";
+
+/// Prefix of the boolean attributes' names marking a definition type, e.g. `IS_PRODUCT`.
+pub const MARKER_NAME_PREFIX: &str = "IS_";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum DefinitionType {
     Product,
     Modifier,
     Service,
+    Custom(String),
 }
 
 impl DefinitionType {
-    pub fn attribute_id(&self) -> &str {
+    /// Id of the built-in marker attribute of the definition type, none for custom
+    /// definition types.
+    #[deprecated(
+        since = "0.3.0",
+        note = "marker attributes are configurable, see `DefinitionTypeMarkers::marker_for`"
+    )]
+    pub fn attribute_id(&self) -> Option<&'static str> {
+        match self {
+            DefinitionType::Product => Some(PRODUCT_ATTRIBUTE_ID),
+            DefinitionType::Modifier => Some(MODIFIER_ATTRIBUTE_ID),
+            DefinitionType::Service => Some(SERVICE_ATTRIBUTE_ID),
+            DefinitionType::Custom(_) => None,
+        }
+    }
+
+    /// Definition type marked by a boolean attribute named `IS_<TYPE>`.
+    pub fn from_marker_name(marker_name: &str) -> Option<DefinitionType> {
+        let definition_type = marker_name.strip_prefix(MARKER_NAME_PREFIX)?;
+
+        match definition_type {
+            "" => None,
+            "PRODUCT" => Some(DefinitionType::Product),
+            "MODIFIER" => Some(DefinitionType::Modifier),
+            "SERVICE" => Some(DefinitionType::Service),
+            _ => Some(DefinitionType::Custom(definition_type.to_lowercase())),
+        }
+    }
+}
+
+/// Boolean attributes whose `true` value marks the definition type of a value.
+#[derive(Debug, Clone)]
+pub struct DefinitionTypeMarkers {
+    markers: Vec<(String, DefinitionType)>,
+}
+
+impl DefinitionTypeMarkers {
+    /// Markers without any registered attribute.
+    pub fn empty() -> DefinitionTypeMarkers {
+        DefinitionTypeMarkers {
+            markers: Vec::new(),
+        }
+    }
+
+    /// Markers declared by the definition among the marker attribute ids, typed by
+    /// their attribute's `IS_<TYPE>` name. Attributes which are not listed are never
+    /// markers, whatever their name, nor are listed attributes which are not boolean.
+    pub fn from_definition(
+        definition: &Definition,
+        marker_attribute_ids: &[String],
+    ) -> DefinitionTypeMarkers {
+        let mut definition_type_markers = DefinitionTypeMarkers::empty();
+
+        for category in definition.categories() {
+            for attribute in category.attributes {
+                if attribute.data_type != "boolean" || !marker_attribute_ids.contains(&attribute.id)
+                {
+                    continue;
+                }
+
+                if let Some(definition_type) = DefinitionType::from_marker_name(&attribute.name) {
+                    definition_type_markers.register_marker(attribute.id, definition_type);
+                }
+            }
+        }
+
+        definition_type_markers
+    }
+
+    /// Registers the attribute as marker of the definition type, replacing any
+    /// previous registration of the attribute.
+    pub fn register_marker(&mut self, attribute_id: String, definition_type: DefinitionType) {
+        self.markers
+            .retain(|(marker_attribute_id, _)| marker_attribute_id != &attribute_id);
+        self.markers.push((attribute_id, definition_type));
+    }
+
+    pub fn markers(&self) -> impl Iterator<Item = (&String, &DefinitionType)> {
+        self.markers
+            .iter()
+            .map(|(attribute_id, definition_type)| (attribute_id, definition_type))
+    }
+
+    /// First attribute registered as marker of the definition type.
+    pub fn marker_for(&self, definition_type: &DefinitionType) -> Option<&String> {
+        self.markers
+            .iter()
+            .find(|(_, marker_definition_type)| marker_definition_type == definition_type)
+            .map(|(attribute_id, _)| attribute_id)
+    }
+
+    /// Detects the definition type of the value, failing when no marker is present,
+    /// when a present marker is not `true` or when markers of different types are present.
+    pub fn detect(&self, value: &Map<String, Value>) -> Result<DefinitionType, Error> {
+        match self.find(value)? {
+            Some(definition_type) => Ok(definition_type),
+            None => Err(Error::new(
+                ErrorKind::InvalidValue,
+                "definition type not found",
+            )),
+        }
+    }
+
+    /// Same as [`DefinitionTypeMarkers::detect`], except that a value without markers
+    /// has no definition type.
+    pub fn find(&self, value: &Map<String, Value>) -> Result<Option<DefinitionType>, Error> {
+        let mut detected_definition_type: Option<(&String, &DefinitionType)> = None;
+
+        for (attribute_id, definition_type) in self.markers.as_slice() {
+            match value.get(attribute_id) {
+                Some(Value::Bool(true)) => (),
+                Some(Value::Bool(false)) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidValue,
+                        format!(
+                            "definition type marker '{}' of '{:?}' is false",
+                            attribute_id, definition_type
+                        ),
+                    ))
+                }
+                Some(_) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidValue,
+                        format!(
+                            "definition type marker '{}' of '{:?}' is not a boolean",
+                            attribute_id, definition_type
+                        ),
+                    ))
+                }
+                None => continue,
+            }
+
+            match detected_definition_type {
+                Some((detected_attribute_id, detected_type))
+                    if detected_type != definition_type =>
+                {
+                    return Err(Error::new(
+                        ErrorKind::DefinitionTypeConflict,
+                        format!(
+                            "definition type markers '{}' of '{:?}' and '{}' of '{:?}' conflict",
+                            detected_attribute_id, detected_type, attribute_id, definition_type
+                        ),
+                    ))
+                }
+                Some(_) => (),
+                None => detected_definition_type = Some((attribute_id, definition_type)),
+            }
+        }
+
+        Ok(detected_definition_type.map(|(_, definition_type)| definition_type.clone()))
+    }
+}
+
+impl Default for DefinitionTypeMarkers {
+    fn default() -> Self {
+        let mut definition_type_markers = DefinitionTypeMarkers::empty();
+
+        definition_type_markers
+            .register_marker(PRODUCT_ATTRIBUTE_ID.to_string(), DefinitionType::Product);
+        definition_type_markers
+            .register_marker(MODIFIER_ATTRIBUTE_ID.to_string(), DefinitionType::Modifier);
+        definition_type_markers
+            .register_marker(SERVICE_ATTRIBUTE_ID.to_string(), DefinitionType::Service);
+
+        definition_type_markers
+    }
+}
+
+/// Where `SchemaValidator` takes the definition type markers from.
+#[derive(Debug, Clone)]
+pub enum DefinitionTypeMarkerSource {
+    Configured(DefinitionTypeMarkers),
+    /// Markers declared by the validated definition among these marker attribute ids,
+    /// see [`DefinitionTypeMarkers::from_definition`].
+    Definition(Vec<String>),
+}
+
+impl DefinitionTypeMarkerSource {
+    pub fn markers(&self, definition: &Definition) -> DefinitionTypeMarkers {
         match self {
-            DefinitionType::Product => "4ed908eb-50b6-4faa-9baa-a7a897cec30f",
-            DefinitionType::Modifier => "d22202c1-44cb-471f-9c69-07f7eea1b9bf",
-            DefinitionType::Service => "70b0d023-20e6-45cb-9654-e5fd42749642",
+            DefinitionTypeMarkerSource::Configured(markers) => markers.clone(),
+            DefinitionTypeMarkerSource::Definition(marker_attribute_ids) => {
+                DefinitionTypeMarkers::from_definition(definition, marker_attribute_ids)
+            }
+        }
+    }
+}
+
+impl Default for DefinitionTypeMarkerSource {
+    fn default() -> Self {
+        DefinitionTypeMarkerSource::Configured(DefinitionTypeMarkers::default())
+    }
+}
+
+/// Detection of a value's definition type from its markers and, when configured,
+/// from the root category of its category chain.
+#[derive(Debug, Clone, Default)]
+pub struct DefinitionTypeDetection {
+    marker_source: DefinitionTypeMarkerSource,
+    roots: HashMap<String, DefinitionType>,
+}
+
+impl DefinitionTypeDetection {
+    pub fn set_marker_source(&mut self, marker_source: DefinitionTypeMarkerSource) {
+        self.marker_source = marker_source;
+    }
+
+    /// Values whose category chain starts at the root category are of the definition type.
+    pub fn register_root(&mut self, category_id: String, definition_type: DefinitionType) {
+        self.roots.insert(category_id, definition_type);
+    }
+
+    pub fn markers(&self, definition: &Definition) -> DefinitionTypeMarkers {
+        self.marker_source.markers(definition)
+    }
+
+    /// Definition type of the root category, if registered.
+    pub fn root_definition_type(&self, category_chain: &CategoryChain) -> Option<&DefinitionType> {
+        category_chain
+            .root()
+            .and_then(|root_category_id| self.roots.get(root_category_id))
+    }
+
+    /// Detects the value's definition type. When the chain's root is registered, the
+    /// markers become optional but must agree with the root's definition type.
+    pub fn detect(
+        &self,
+        definition: &Definition,
+        category_chain: &CategoryChain,
+        value: &Map<String, Value>,
+    ) -> Result<DefinitionType, Error> {
+        let markers = self.markers(definition);
+
+        let root_definition_type = match self.root_definition_type(category_chain) {
+            Some(root_definition_type) => root_definition_type,
+            None => return markers.detect(value),
+        };
+
+        match markers.find(value)? {
+            Some(marker_definition_type) if &marker_definition_type != root_definition_type => {
+                Err(Error::new(
+                    ErrorKind::DefinitionTypeConflict,
+                    format!(
+                        "definition type marker of '{:?}' conflicts with root category's definition type '{:?}'",
+                        marker_definition_type, root_definition_type
+                    ),
+                ))
+            }
+            _ => Ok(root_definition_type.clone()),
         }
     }
 }
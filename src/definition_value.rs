@@ -1,43 +1,146 @@
 use crate::category_chain::CategoryChain;
-use crate::definition_type::DefinitionType;
+use crate::definition_type::{DefinitionType, DefinitionTypeDetection};
 use crate::error::{Error, ErrorKind};
+use crate::schema_validator::{SchemaValidator, VALUE_TYPE, VALUE_VERSION};
+use crate::version_policy::VersionPolicyKind;
 use cooplan_definitions_lib::definition::Definition;
-use serde::Deserialize;
+use cooplan_definitions_lib::validated_source_attribute::ValidatedSourceAttribute;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 /// Validated, definition scoped, JSON value.
-#[derive(Debug, Deserialize)]
+///
+/// Serialized values are read back either with
+/// [`DefinitionValue::deserialize_and_validate`], or, for values read from trusted
+/// stores only, with [`DefinitionValue::from_trusted_json`].
+#[derive(Debug, Serialize)]
 pub struct DefinitionValue {
     definition: String,
     definition_type: DefinitionType,
     category_chain: CategoryChain,
     value: Map<String, Value>,
+    attributes: Vec<ValidatedSourceAttribute>,
+    version_policy: VersionPolicyKind,
+}
+
+/// Serialized form of a [`DefinitionValue`], not validated yet.
+#[derive(Deserialize)]
+struct SerializedDefinitionValue {
+    definition: String,
+    definition_type: DefinitionType,
+    category_chain: CategoryChain,
+    value: Map<String, Value>,
+    #[serde(default)]
+    attributes: Vec<ValidatedSourceAttribute>,
+    #[serde(default)]
+    version_policy: VersionPolicyKind,
+}
+
+impl SerializedDefinitionValue {
+    fn parse(serialized_value: &str) -> Result<SerializedDefinitionValue, Error> {
+        match serde_json::from_str(serialized_value) {
+            Ok(serialized_value) => Ok(serialized_value),
+            Err(error) => Err(Error::new(
+                ErrorKind::DeserializationFailure,
+                format!("failed to deserialize definition value: {}", error),
+            )),
+        }
+    }
 }
 
 impl DefinitionValue {
-    pub fn try_new(
+    /// Builds a value already validated by [`SchemaValidator`], which is the only
+    /// way of obtaining validated values.
+    pub(crate) fn try_new(
         definition: &Definition,
         category_chain: CategoryChain,
         value: Map<String, Value>,
+        attributes: Vec<ValidatedSourceAttribute>,
+        version_policy: VersionPolicyKind,
+        definition_type_detection: &DefinitionTypeDetection,
     ) -> Result<DefinitionValue, Error> {
-        let definition_type = if value.contains_key(DefinitionType::Product.attribute_id()) {
-            DefinitionType::Product
-        } else if value.contains_key(DefinitionType::Modifier.attribute_id()) {
-            DefinitionType::Modifier
-        } else if value.contains_key(DefinitionType::Service.attribute_id()) {
-            DefinitionType::Service
-        } else {
-            return Err(Error::new(
-                ErrorKind::InvalidValue,
-                "definition type not found",
-            ));
-        };
+        let definition_type =
+            definition_type_detection.detect(definition, &category_chain, &value)?;
 
         Ok(DefinitionValue {
             definition: definition.version(),
             category_chain,
             definition_type,
             value,
+            attributes,
+            version_policy,
+        })
+    }
+
+    /// Deserializes a previously serialized value and validates it again against
+    /// `definition`, rejecting payloads which do not match what `schema_validator`
+    /// would have produced.
+    pub fn deserialize_and_validate(
+        serialized_value: &str,
+        schema_validator: &mut SchemaValidator,
+        definition: Definition,
+    ) -> Result<DefinitionValue, Error> {
+        let unverified_value = SerializedDefinitionValue::parse(serialized_value)?;
+
+        let value_type = match unverified_value.category_chain.leaf() {
+            Some(value_type) => value_type.clone(),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidValue,
+                    "definition value has an empty category chain",
+                ))
+            }
+        };
+
+        let mut object = unverified_value.value.clone();
+        object.insert(
+            VALUE_VERSION.to_string(),
+            Value::String(unverified_value.definition.clone()),
+        );
+        object.insert(VALUE_TYPE.to_string(), Value::String(value_type));
+
+        let definition_value = schema_validator.validate_object(object, definition)?;
+
+        if definition_value.definition_type != unverified_value.definition_type {
+            return Err(Error::new(
+                ErrorKind::InvalidValue,
+                format!(
+                    "definition type '{:?}' != validated definition type '{:?}'",
+                    unverified_value.definition_type, definition_value.definition_type
+                ),
+            ));
+        }
+
+        if definition_value.category_chain != unverified_value.category_chain {
+            return Err(Error::new(
+                ErrorKind::InvalidValue,
+                "category chain does not match the definition's category chain",
+            ));
+        }
+
+        if definition_value.value != unverified_value.value {
+            return Err(Error::new(
+                ErrorKind::InvalidValue,
+                "value contains attributes which are not part of its category",
+            ));
+        }
+
+        Ok(definition_value)
+    }
+
+    /// Deserializes a previously serialized value without validating it at all,
+    /// hence it must only be used for values read from trusted stores. Untrusted
+    /// payloads must go through [`DefinitionValue::deserialize_and_validate`].
+    pub fn from_trusted_json(serialized_value: &str) -> Result<DefinitionValue, Error> {
+        let trusted_value = SerializedDefinitionValue::parse(serialized_value)?;
+
+        Ok(DefinitionValue {
+            definition: trusted_value.definition,
+            definition_type: trusted_value.definition_type,
+            category_chain: trusted_value.category_chain,
+            value: trusted_value.value,
+            attributes: trusted_value.attributes,
+            version_policy: trusted_value.version_policy,
         })
     }
 
@@ -53,7 +156,117 @@ impl DefinitionValue {
         &self.category_chain
     }
 
-    pub fn definition_type(&self) -> DefinitionType {
-        self.definition_type
+    pub fn definition_type(&self) -> &DefinitionType {
+        &self.definition_type
+    }
+
+    /// Version policy which accepted the value's version.
+    pub fn version_policy(&self) -> VersionPolicyKind {
+        self.version_policy
+    }
+
+    /// Attributes the value has been validated against, alongside their values.
+    pub fn attributes(&self) -> impl Iterator<Item = (&ValidatedSourceAttribute, &Value)> {
+        self.attributes.iter().filter_map(|attribute| {
+            self.value
+                .get(&attribute.id)
+                .map(|attribute_value| (attribute, attribute_value))
+        })
+    }
+
+    /// Reads the attribute, found by id or name, of data type `string`.
+    pub fn get_str(&self, attribute: &str) -> Result<&str, Error> {
+        let (attribute, attribute_value) = self.try_get_attribute(attribute, "string")?;
+
+        match attribute_value.as_str() {
+            Some(string) => Ok(string),
+            None => Err(type_mismatch_error(attribute, "string")),
+        }
     }
+
+    /// Reads the attribute, found by id or name, of data type `integer`.
+    pub fn get_i64(&self, attribute: &str) -> Result<i64, Error> {
+        let (attribute, attribute_value) = self.try_get_attribute(attribute, "integer")?;
+
+        match attribute_value.as_i64() {
+            Some(integer) => Ok(integer),
+            None => Err(type_mismatch_error(attribute, "integer")),
+        }
+    }
+
+    /// Reads the attribute, found by id or name, of data type `decimal`.
+    pub fn get_decimal(&self, attribute: &str) -> Result<f64, Error> {
+        let (attribute, attribute_value) = self.try_get_attribute(attribute, "decimal")?;
+
+        match attribute_value.as_f64() {
+            Some(decimal) => Ok(decimal),
+            None => Err(type_mismatch_error(attribute, "decimal")),
+        }
+    }
+
+    /// Reads the attribute, found by id or name, of data type `boolean`.
+    pub fn get_bool(&self, attribute: &str) -> Result<bool, Error> {
+        let (attribute, attribute_value) = self.try_get_attribute(attribute, "boolean")?;
+
+        match attribute_value.as_bool() {
+            Some(boolean) => Ok(boolean),
+            None => Err(type_mismatch_error(attribute, "boolean")),
+        }
+    }
+
+    /// Attribute, found by id or name, alongside its value, as long as the attribute
+    /// is of the requested data type.
+    fn try_get_attribute(
+        &self,
+        attribute: &str,
+        data_type: &str,
+    ) -> Result<(&ValidatedSourceAttribute, &Value), Error> {
+        let found_attribute = match self
+            .attributes
+            .iter()
+            .find(|candidate| candidate.id == attribute)
+            .or_else(|| {
+                self.attributes
+                    .iter()
+                    .find(|candidate| candidate.name == attribute)
+            }) {
+            Some(found_attribute) => found_attribute,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::AttributeNotFound,
+                    format!(
+                        "attribute '{}' is not part of the value's category",
+                        attribute
+                    ),
+                ))
+            }
+        };
+
+        if found_attribute.data_type != data_type {
+            return Err(type_mismatch_error(found_attribute, data_type));
+        }
+
+        match self.value.get(&found_attribute.id) {
+            Some(attribute_value) => Ok((found_attribute, attribute_value)),
+            None => Err(Error::new(
+                ErrorKind::AttributeNotFound,
+                format!(
+                    "attribute '{}' ({}) has no value",
+                    found_attribute.name, found_attribute.id
+                ),
+            )
+            .with_attribute_id(found_attribute.id.clone())),
+        }
+    }
+}
+
+fn type_mismatch_error(attribute: &ValidatedSourceAttribute, requested_type: &str) -> Error {
+    Error::new(
+        ErrorKind::AttributeTypeMismatch,
+        format!(
+            "attribute '{}' ({}) of data type '{}' cannot be read as {}",
+            attribute.name, attribute.id, attribute.data_type, requested_type
+        ),
+    )
+    .with_attribute_id(attribute.id.clone())
 }
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, ErrorKind};
+use crate::report::ValidationOutcome;
+use crate::schema_validator::VALUE_TYPE;
+use crate::source_map::{member_pointer, SourceMap};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+const BOLD_CYAN: &str = "\x1b[1;36m";
+
+/// Renders compiler-style diagnostics of failing values, showing the line of the
+/// offending token underlined and labelled with what was expected, e.g.:
+///
+/// ```text
+/// error[InvalidValue]: failed to convert attribute value to integer
+///  --> apple.json:4:9
+///   |
+/// 4 |   "11": "many",
+///   |         ^^^^^^ expected integer (count)
+///   |
+///   = help: integer values are whole numbers from -9223372036854775808 to 9223372036854775807
+/// ```
+pub struct DiagnosticRenderer {
+    data_type_help: HashMap<String, String>,
+    colored: bool,
+}
+
+impl DiagnosticRenderer {
+    fn initialize_base_data_type_help(data_type_help: &mut HashMap<String, String>) {
+        data_type_help.insert(
+            "string".to_string(),
+            "string values are quoted, e.g. \"Pear\"".to_string(),
+        );
+        data_type_help.insert(
+            "integer".to_string(),
+            format!(
+                "integer values are whole numbers from {} to {}",
+                i64::MIN,
+                i64::MAX
+            ),
+        );
+        data_type_help.insert(
+            "decimal".to_string(),
+            "decimal values are numbers, e.g. 15.39".to_string(),
+        );
+        data_type_help.insert(
+            "boolean".to_string(),
+            "boolean values are `true` or `false`".to_string(),
+        );
+    }
+
+    /// Registers the help shown for invalid values of the data type, such as the
+    /// range its validation allows.
+    pub fn register_data_type_help(&mut self, data_type: String, help: String) {
+        self.data_type_help.insert(data_type, help);
+    }
+
+    /// Whether diagnostics are colored with ANSI escape codes, for terminals.
+    pub fn set_colored(&mut self, colored: bool) {
+        self.colored = colored;
+    }
+
+    /// Diagnostic of the outcome's error, none for valid outcomes. The source is
+    /// the text the value was validated from.
+    pub fn render(
+        &self,
+        outcome: &ValidationOutcome,
+        source: &str,
+        definition: &Definition,
+    ) -> Option<String> {
+        let error = outcome.error.as_ref()?;
+        let attribute = error
+            .attribute_id
+            .as_ref()
+            .and_then(|attribute_id| find_attribute(definition, attribute_id));
+
+        let mut diagnostic = format!(
+            "{}{}\n",
+            self.paint(BOLD_RED, &format!("error[{:?}]", error.kind())),
+            self.paint(BOLD, &format!(": {}", error.message))
+        );
+
+        let help = self.help(error, attribute.as_ref(), source, definition);
+
+        let (span, line_number) = match (error.span, outcome.error_position()) {
+            (Some(span), Some((line_number, _))) => (span, line_number),
+            _ => {
+                diagnostic.push_str(&format!(
+                    " {} {}\n",
+                    self.paint(BOLD_BLUE, "-->"),
+                    outcome.location()
+                ));
+
+                for help in help {
+                    diagnostic.push_str(&self.help_line(" ", &help));
+                }
+
+                return Some(diagnostic);
+            }
+        };
+
+        let gutter = " ".repeat(line_number.to_string().len());
+        let source_line = source.lines().nth(span.start.line - 1).unwrap_or("");
+
+        let padding: String = source_line
+            .chars()
+            .take(span.start.column - 1)
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+        let underline_length = if span.end.line == span.start.line {
+            span.end.column - span.start.column
+        } else {
+            source_line.chars().count() + 1 - span.start.column
+        };
+        let mut underline = "^".repeat(underline_length.max(1));
+
+        if let Some(label) = label(error, attribute.as_ref(), source, definition) {
+            underline.push_str(&format!(" {}", label));
+        }
+
+        diagnostic.push_str(&format!(
+            "{}{} {}\n",
+            gutter,
+            self.paint(BOLD_BLUE, "-->"),
+            outcome.error_location()
+        ));
+        diagnostic.push_str(&format!("{} {}\n", gutter, self.paint(BOLD_BLUE, "|")));
+        diagnostic.push_str(&format!(
+            "{} {}\n",
+            self.paint(BOLD_BLUE, &format!("{} |", line_number)),
+            source_line
+        ));
+        diagnostic.push_str(&format!(
+            "{} {} {}{}\n",
+            gutter,
+            self.paint(BOLD_BLUE, "|"),
+            padding,
+            self.paint(BOLD_RED, &underline)
+        ));
+
+        if !help.is_empty() {
+            diagnostic.push_str(&format!("{} {}\n", gutter, self.paint(BOLD_BLUE, "|")));
+        }
+
+        for help in help {
+            diagnostic.push_str(&self.help_line(&gutter, &help));
+        }
+
+        Some(diagnostic)
+    }
+
+    fn help(
+        &self,
+        error: &Error,
+        attribute: Option<&ValidatedSourceAttribute>,
+        source: &str,
+        definition: &Definition,
+    ) -> Vec<String> {
+        let mut help: Vec<String> = Vec::new();
+
+        if let Some(attribute) = attribute {
+            if let Some(data_type_help) = self.data_type_help.get(&attribute.data_type) {
+                help.push(data_type_help.clone());
+            }
+
+            if let Some(unit) = &attribute.unit {
+                help.push(format!("{} is measured in {}", attribute.name, unit));
+            }
+
+            return help;
+        }
+
+        let type_span = SourceMap::parse(source)
+            .ok()
+            .and_then(|source_map| source_map.value(&member_pointer("", VALUE_TYPE)));
+
+        if error.kind() == ErrorKind::TypeAttributeMissing
+            || (error.span.is_some() && error.span == type_span)
+        {
+            let categories: Vec<String> = definition
+                .categories()
+                .into_iter()
+                .filter(|category| category.selectable_as_last)
+                .map(|category| format!("\"{}\" ({})", category.id, category.name))
+                .collect();
+
+            help.push(format!(
+                "\"{}\" is one of the categories selectable as last: {}",
+                VALUE_TYPE,
+                categories.join(", ")
+            ));
+        }
+
+        help
+    }
+
+    fn help_line(&self, gutter: &str, help: &str) -> String {
+        format!(
+            "{} {} {}: {}\n",
+            gutter,
+            self.paint(BOLD_BLUE, "="),
+            self.paint(BOLD_CYAN, "help"),
+            help
+        )
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.colored {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Default for DiagnosticRenderer {
+    fn default() -> Self {
+        let mut data_type_help: HashMap<String, String> = HashMap::new();
+
+        DiagnosticRenderer::initialize_base_data_type_help(&mut data_type_help);
+
+        DiagnosticRenderer {
+            data_type_help,
+            colored: false,
+        }
+    }
+}
+
+/// What was expected of the offending token, if the error tells.
+fn label(
+    error: &Error,
+    attribute: Option<&ValidatedSourceAttribute>,
+    source: &str,
+    definition: &Definition,
+) -> Option<String> {
+    match (error.kind(), attribute) {
+        (ErrorKind::ValidationNotRegistered, Some(attribute)) => Some(format!(
+            "no validation registered for {} ({})",
+            attribute.data_type, attribute.name
+        )),
+        (_, Some(attribute)) if is_missing(source, &attribute.id) => Some(format!(
+            "missing {} ({})",
+            attribute.name, attribute.data_type
+        )),
+        (_, Some(attribute)) => Some(format!(
+            "expected {} ({})",
+            attribute.data_type, attribute.name
+        )),
+        (ErrorKind::VersionAttributeMissing, None) => Some("missing \"version\"".to_string()),
+        (ErrorKind::TypeAttributeMissing, None) => Some("missing \"type\"".to_string()),
+        (ErrorKind::ValueDefinitionMismatch, None) => Some(format!(
+            "incompatible with definition version {}",
+            definition.version()
+        )),
+        (ErrorKind::UnknownDefinitionVersion, None) => {
+            Some("unknown definition version".to_string())
+        }
+        (ErrorKind::DeserializationFailure, None) => Some("invalid JSON".to_string()),
+        _ => None,
+    }
+}
+
+fn is_missing(source: &str, attribute_id: &str) -> bool {
+    match serde_json::from_str::<Map<String, Value>>(source) {
+        Ok(object) => !object.contains_key(attribute_id),
+        Err(_) => false,
+    }
+}
+
+fn find_attribute(definition: &Definition, attribute_id: &str) -> Option<ValidatedSourceAttribute> {
+    definition
+        .categories()
+        .into_iter()
+        .flat_map(|category| category.attributes)
+        .find(|attribute| attribute.id == attribute_id)
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::source_map::SourceSpan;
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ErrorKind {
     DeserializationFailure,
@@ -8,17 +10,48 @@ pub enum ErrorKind {
     InvalidValue,
     ValueDefinitionMismatch,
     ValidationNotRegistered,
+    AttributeNotFound,
+    AttributeTypeMismatch,
+    DataTypeNotRegistered,
+    UnsupportedSchema,
+    MigrationNotFound,
+    MigrationFailure,
+    UnknownDefinitionVersion,
+    DefinitionTypeConflict,
+    InvalidDefinition,
+    InvalidArgument,
+    IoFailure,
 }
 
 #[derive(Debug)]
 pub struct Error {
     pub kind: ErrorKind,
     pub message: String,
+    /// Attribute the error relates to, if any.
+    pub attribute_id: Option<String>,
+    /// Token of the validated source the error relates to, if the value was
+    /// validated from its source.
+    pub span: Option<SourceSpan>,
 }
 
 impl Error {
     pub fn new(kind: ErrorKind, message: impl Into<String>) -> Error {
-        Error { kind, message: message.into() }
+        Error {
+            kind,
+            message: message.into(),
+            attribute_id: None,
+            span: None,
+        }
+    }
+
+    pub fn with_attribute_id(mut self, attribute_id: impl Into<String>) -> Error {
+        self.attribute_id = Some(attribute_id.into());
+        self
+    }
+
+    pub fn with_span(mut self, span: SourceSpan) -> Error {
+        self.span = Some(span);
+        self
     }
 
     pub fn kind(&self) -> ErrorKind {
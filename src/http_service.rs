@@ -0,0 +1,337 @@
+use std::fs;
+
+use serde_json::{json, Value};
+
+use crate::cli::{has_extension, load_definition};
+use crate::definition_registry::DefinitionRegistry;
+use crate::error::{Error, ErrorKind};
+use crate::json_schema::JsonSchemaExporter;
+use crate::report::{definition_value_to_json, error_to_json};
+use crate::schema_validator::SchemaValidator;
+
+#[cfg(feature = "yaml")]
+const DEFINITION_EXTENSIONS: &[&str] = &["json", "yaml", "yml"];
+#[cfg(not(feature = "yaml"))]
+const DEFINITION_EXTENSIONS: &[&str] = &["json"];
+
+/// Largest request body read, in bytes, larger ones being rejected.
+pub const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// Status and JSON body answering a request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> HttpResponse {
+        HttpResponse { status: 200, body }
+    }
+
+    fn error(status: u16, error: &Error) -> HttpResponse {
+        HttpResponse {
+            status,
+            body: json!({ "error": error_to_json(error) }),
+        }
+    }
+}
+
+/// Validation over HTTP, independent of any HTTP server, serving:
+///
+/// - `POST /validate`, validating the body's value against the definition of its version.
+/// - `POST /validate/batch`, validating each value of the body's array.
+/// - `GET /definitions/{version}/categories/{id}/schema`, exporting the category's
+///   JSON Schema.
+///
+/// Errors are answered as `{ "error": { "kind", "message", "attribute_id" } }`, the
+/// kind being the name of the `ErrorKind`.
+pub struct ValidationService {
+    definition_registry: DefinitionRegistry,
+    schema_validator: SchemaValidator,
+    json_schema_exporter: JsonSchemaExporter,
+}
+
+impl ValidationService {
+    pub fn new(
+        definition_registry: DefinitionRegistry,
+        schema_validator: SchemaValidator,
+        json_schema_exporter: JsonSchemaExporter,
+    ) -> ValidationService {
+        ValidationService {
+            definition_registry,
+            schema_validator,
+            json_schema_exporter,
+        }
+    }
+
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> HttpResponse {
+        let path = match path.split_once('?') {
+            Some((path, _)) => path,
+            None => path,
+        };
+
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        match (method, segments.as_slice()) {
+            ("POST", ["validate"]) => self.validate(body),
+            ("POST", ["validate", "batch"]) => self.validate_batch(body),
+            ("GET", ["definitions", version, "categories", category_id, "schema"]) => {
+                self.export_schema(version, category_id)
+            }
+            (_, ["validate"])
+            | (_, ["validate", "batch"])
+            | (_, ["definitions", _, "categories", _, "schema"]) => HttpResponse::error(
+                405,
+                &Error::new(
+                    ErrorKind::InvalidArgument,
+                    format!("method '{}' is not allowed for '{}'", method, path),
+                ),
+            ),
+            _ => HttpResponse::error(
+                404,
+                &Error::new(
+                    ErrorKind::InvalidArgument,
+                    format!("no route found for '{} {}'", method, path),
+                ),
+            ),
+        }
+    }
+
+    fn validate(&mut self, body: &str) -> HttpResponse {
+        let result = self
+            .definition_registry
+            .validate(&mut self.schema_validator, body.to_string());
+
+        match result {
+            Ok(definition_value) => HttpResponse::ok(definition_value_to_json(&definition_value)),
+            Err(error) if error.kind() == ErrorKind::DeserializationFailure => {
+                HttpResponse::error(400, &error)
+            }
+            Err(error) => HttpResponse::error(422, &error),
+        }
+    }
+
+    fn validate_batch(&mut self, body: &str) -> HttpResponse {
+        let values = match serde_json::from_str(body) {
+            Ok(Value::Array(values)) => values,
+            Ok(_) => {
+                return HttpResponse::error(
+                    400,
+                    &Error::new(
+                        ErrorKind::DeserializationFailure,
+                        "batch body must be an array of values",
+                    ),
+                )
+            }
+            Err(error) => {
+                return HttpResponse::error(
+                    400,
+                    &Error::new(
+                        ErrorKind::DeserializationFailure,
+                        format!("failed to deserialize batch: {}", error),
+                    ),
+                )
+            }
+        };
+
+        let mut invalid = 0;
+        let mut results: Vec<Value> = Vec::new();
+
+        for value in values {
+            let result = match value {
+                Value::Object(object) => self
+                    .definition_registry
+                    .validate_object(&mut self.schema_validator, object),
+                _ => Err(Error::new(
+                    ErrorKind::DeserializationFailure,
+                    "value must be an object",
+                )),
+            };
+
+            match result {
+                Ok(definition_value) => results.push(definition_value_to_json(&definition_value)),
+                Err(error) => {
+                    invalid += 1;
+                    results.push(json!({ "valid": false, "error": error_to_json(&error) }));
+                }
+            }
+        }
+
+        HttpResponse::ok(json!({
+            "valid": results.len() - invalid,
+            "invalid": invalid,
+            "results": results,
+        }))
+    }
+
+    fn export_schema(&self, version: &str, category_id: &str) -> HttpResponse {
+        let definition = match self.definition_registry.get(version) {
+            Some(definition) => definition,
+            None => {
+                return HttpResponse::error(
+                    404,
+                    &Error::new(
+                        ErrorKind::UnknownDefinitionVersion,
+                        format!("no definition registered for version '{}'", version),
+                    ),
+                )
+            }
+        };
+
+        if !definition
+            .categories()
+            .iter()
+            .any(|category| category.id == category_id)
+        {
+            return HttpResponse::error(
+                404,
+                &Error::new(
+                    ErrorKind::InvalidValue,
+                    format!("category id '{}' not found in definition", category_id),
+                ),
+            );
+        }
+
+        match self
+            .json_schema_exporter
+            .export_category(definition, &category_id.to_string())
+        {
+            Ok(schema) => HttpResponse::ok(schema),
+            Err(error) => HttpResponse::error(500, &error),
+        }
+    }
+}
+
+/// Registers the definition of every JSON file of the directory, and of every YAML
+/// file when the `yaml` feature is enabled, in file name order.
+pub fn load_definitions(directory: &str) -> Result<DefinitionRegistry, Error> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::IoFailure,
+                format!("failed to read directory '{}': {}", directory, error),
+            ))
+        }
+    };
+
+    let mut paths: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .filter(|path| has_extension(path, DEFINITION_EXTENSIONS))
+        .collect();
+    paths.sort();
+
+    let mut definition_registry = DefinitionRegistry::default();
+
+    for path in paths {
+        definition_registry.register_definition(load_definition(&path)?);
+    }
+
+    Ok(definition_registry)
+}
+
+/// Decodes `%XX` escapes, keeping malformed ones as they are.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = match (bytes[index], bytes.get(index + 1..index + 3)) {
+            (b'%', Some(hex)) => std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Answers the server's requests until it is unblocked or fails.
+#[cfg(feature = "http")]
+pub fn serve(server: &tiny_http::Server, validation_service: &mut ValidationService) {
+    for mut request in server.incoming_requests() {
+        let response = match read_body(&mut request) {
+            Ok(body) => {
+                validation_service.handle(request.method().as_str(), request.url(), body.as_str())
+            }
+            Err(response) => response,
+        };
+
+        let content_type =
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                .expect("static header is valid");
+
+        let http_response = tiny_http::Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(content_type);
+
+        if let Err(error) = request.respond(http_response) {
+            log::warn!("failed to respond to request: {}", error);
+        }
+    }
+}
+
+/// Body of the request, or the response rejecting it when it is unreadable or
+/// larger than [`MAX_BODY_SIZE`].
+#[cfg(feature = "http")]
+fn read_body(request: &mut tiny_http::Request) -> Result<String, HttpResponse> {
+    use std::io::Read;
+
+    let too_large = HttpResponse::error(
+        413,
+        &Error::new(
+            ErrorKind::DeserializationFailure,
+            format!("request body exceeds {} bytes", MAX_BODY_SIZE),
+        ),
+    );
+
+    if let Some(length) = request.body_length() {
+        if length as u64 > MAX_BODY_SIZE {
+            return Err(too_large);
+        }
+    }
+
+    let mut body = String::new();
+
+    if let Err(error) = request
+        .as_reader()
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_string(&mut body)
+    {
+        return Err(HttpResponse::error(
+            400,
+            &Error::new(
+                ErrorKind::DeserializationFailure,
+                format!("failed to read request body: {}", error),
+            ),
+        ));
+    }
+
+    if body.len() as u64 > MAX_BODY_SIZE {
+        return Err(too_large);
+    }
+
+    Ok(body)
+}
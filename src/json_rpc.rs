@@ -0,0 +1,344 @@
+use std::io::{BufRead, Write};
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::{json, Map, Value};
+
+use crate::category_attributes::collect_from_definition_and_category;
+use crate::cli::load_definition;
+use crate::definition_registry::DefinitionRegistry;
+use crate::error::{Error, ErrorKind};
+use crate::report::{definition_value_to_json, error_to_json};
+use crate::schema_validator::SchemaValidator;
+
+pub const JSON_RPC_VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// Error of the crate, whose `error_to_json` representation is the error's data.
+pub const SERVER_ERROR: i64 = -32000;
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> RpcError {
+        RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+
+        if let Some(data) = &self.data {
+            error["data"] = data.clone();
+        }
+
+        error
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(error: Error) -> Self {
+        RpcError {
+            code: SERVER_ERROR,
+            message: error.message.clone(),
+            data: Some(error_to_json(&error)),
+        }
+    }
+}
+
+/// JSON-RPC 2.0 validation service keeping its definitions and validator warm
+/// between requests. Methods:
+///
+/// - `definitions/load`, `{ "path" }` or `{ "definition" }`, registering the
+///   definition and replacing the one of the same version.
+/// - `definitions/remove`, `{ "version" }`.
+/// - `definitions/list`, the registered versions.
+/// - `validate`, `{ "value" }`, validating the value against the definition of its
+///   version. Invalid values are results, with `valid` being `false`.
+/// - `lint`, `{ "version" }` or `{ "definition" }`.
+/// - `attributes`, `{ "version", "category_id" }`, the category's attributes
+///   including the inherited ones.
+/// - `shutdown`, stopping `serve` once answered.
+pub struct JsonRpcService {
+    definition_registry: DefinitionRegistry,
+    schema_validator: SchemaValidator,
+    shutdown_requested: bool,
+}
+
+impl JsonRpcService {
+    pub fn new(
+        definition_registry: DefinitionRegistry,
+        schema_validator: SchemaValidator,
+    ) -> JsonRpcService {
+        JsonRpcService {
+            definition_registry,
+            schema_validator,
+            shutdown_requested: false,
+        }
+    }
+
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    /// Answers newline delimited messages until the input ends or `shutdown` is
+    /// requested, writing one response per line.
+    pub fn serve(&mut self, input: &mut dyn BufRead, output: &mut dyn Write) -> Result<(), Error> {
+        let mut line = String::new();
+
+        while !self.shutdown_requested {
+            line.clear();
+
+            match input.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => (),
+                Err(error) => {
+                    return Err(Error::new(
+                        ErrorKind::IoFailure,
+                        format!("failed to read request: {}", error),
+                    ))
+                }
+            }
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = self.handle_message(&line) {
+                let written = writeln!(output, "{}", response).and_then(|_| output.flush());
+
+                if let Err(error) = written {
+                    return Err(Error::new(
+                        ErrorKind::IoFailure,
+                        format!("failed to write response: {}", error),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Response to the request or batch of requests, none for notifications.
+    pub fn handle_message(&mut self, message: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(error) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, format!("failed to parse request: {}", error)),
+                ))
+            }
+        };
+
+        match message {
+            Value::Array(requests) if requests.is_empty() => Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "batch is empty"),
+            )),
+            Value::Array(requests) => {
+                let responses: Vec<Value> = requests
+                    .into_iter()
+                    .filter_map(|request| self.handle_request(request))
+                    .collect();
+
+                if responses.is_empty() {
+                    None
+                } else {
+                    Some(Value::Array(responses))
+                }
+            }
+            request => self.handle_request(request),
+        }
+    }
+
+    fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let mut request = match request {
+            Value::Object(request)
+                if request.get("jsonrpc") == Some(&Value::from(JSON_RPC_VERSION)) =>
+            {
+                request
+            }
+            _ => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(INVALID_REQUEST, "request is not a JSON-RPC 2.0 object"),
+                ))
+            }
+        };
+
+        let id = request.remove("id");
+
+        let method = match request.remove("method") {
+            Some(Value::String(method)) => method,
+            _ => {
+                return Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    RpcError::new(INVALID_REQUEST, "request's method is not a string"),
+                ))
+            }
+        };
+
+        let params = match request.remove("params") {
+            Some(Value::Object(params)) => params,
+            None | Some(Value::Null) => Map::new(),
+            Some(_) => {
+                return Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    RpcError::new(INVALID_PARAMS, "params must be an object"),
+                ))
+            }
+        };
+
+        let result = self.call(&method, params);
+
+        // Requests without an id are notifications, which are never answered.
+        let id = id?;
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": JSON_RPC_VERSION, "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn call(&mut self, method: &str, mut params: Map<String, Value>) -> Result<Value, RpcError> {
+        match method {
+            "definitions/load" => {
+                let definition = take_definition(&mut params)?;
+                let version = definition.version();
+                let replaced = self
+                    .definition_registry
+                    .register_definition(definition)
+                    .is_some();
+
+                Ok(json!({ "version": version, "replaced": replaced }))
+            }
+            "definitions/remove" => {
+                let version = string_param(&params, "version")?;
+                let removed = self
+                    .definition_registry
+                    .remove_definition(version)
+                    .is_some();
+
+                Ok(json!({ "removed": removed }))
+            }
+            "definitions/list" => {
+                let mut versions: Vec<String> = self
+                    .definition_registry
+                    .definitions()
+                    .map(|definition| definition.version())
+                    .collect();
+                versions.sort();
+
+                Ok(json!({ "versions": versions }))
+            }
+            "validate" => {
+                let value = match params.remove("value") {
+                    Some(Value::Object(value)) => value,
+                    _ => {
+                        return Err(RpcError::new(
+                            INVALID_PARAMS,
+                            "param 'value' must be an object",
+                        ))
+                    }
+                };
+
+                match self
+                    .definition_registry
+                    .validate_object(&mut self.schema_validator, value)
+                {
+                    Ok(definition_value) => Ok(definition_value_to_json(&definition_value)),
+                    Err(error) => Ok(json!({ "valid": false, "error": error_to_json(&error) })),
+                }
+            }
+            "lint" => {
+                let definition = match params.get("version") {
+                    Some(_) => self.registered_definition(&params)?.clone(),
+                    None => take_definition(&mut params)?,
+                };
+
+                let report = self.schema_validator.lint(&definition);
+                let issues: Vec<Value> = report
+                    .issues
+                    .iter()
+                    .map(|issue| {
+                        json!({
+                            "severity": format!("{:?}", issue.severity),
+                            "kind": format!("{:?}", issue.kind),
+                            "category_id": issue.category_id,
+                            "attribute_id": issue.attribute_id,
+                            "message": issue.message,
+                        })
+                    })
+                    .collect();
+
+                Ok(json!({ "has_errors": report.has_errors(), "issues": issues }))
+            }
+            "attributes" => {
+                let category_id = string_param(&params, "category_id")?.to_string();
+                let definition = self.registered_definition(&params)?;
+                let attributes = collect_from_definition_and_category(definition, &category_id)?;
+
+                Ok(json!({ "attributes": attributes }))
+            }
+            "shutdown" => {
+                self.shutdown_requested = true;
+
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method '{}' not found", method),
+            )),
+        }
+    }
+
+    fn registered_definition(&self, params: &Map<String, Value>) -> Result<&Definition, RpcError> {
+        let version = string_param(params, "version")?;
+
+        match self.definition_registry.get(version) {
+            Some(definition) => Ok(definition),
+            None => Err(RpcError::from(Error::new(
+                ErrorKind::UnknownDefinitionVersion,
+                format!("no definition registered for version '{}'", version),
+            ))),
+        }
+    }
+}
+
+fn take_definition(params: &mut Map<String, Value>) -> Result<Definition, RpcError> {
+    if let Some(definition) = params.remove("definition") {
+        return match serde_json::from_value(definition) {
+            Ok(definition) => Ok(definition),
+            Err(error) => Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("param 'definition' is not a definition: {}", error),
+            )),
+        };
+    }
+
+    Ok(load_definition(string_param(params, "path")?)?)
+}
+
+fn string_param<'a>(params: &'a Map<String, Value>, name: &str) -> Result<&'a str, RpcError> {
+    match params.get(name) {
+        Some(Value::String(value)) => Ok(value),
+        _ => Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("param '{}' must be a string", name),
+        )),
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": JSON_RPC_VERSION, "id": id, "error": error.to_json() })
+}
@@ -0,0 +1,615 @@
+use std::collections::HashMap;
+
+use cooplan_definitions_lib::{
+    definition::Definition, source_attribute::SourceAttribute, source_category::SourceCategory,
+    validated_source_category::ValidatedSourceCategory,
+};
+use serde_json::{json, Map, Value};
+
+use crate::category_attributes::{
+    collect_from_category_chain, collect_from_definition_and_category,
+};
+use crate::category_chain::CategoryIndex;
+use crate::error::{Error, ErrorKind};
+use crate::schema_validator::{VALUE_TYPE, VALUE_VERSION};
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Non-standard keyword holding an attribute's unit.
+pub const UNIT_KEYWORD: &str = "x-unit";
+
+/// Exports definition categories as JSON Schema (draft 2020-12) documents.
+pub struct JsonSchemaExporter {
+    schemas: HashMap<String, Value>,
+}
+
+impl JsonSchemaExporter {
+    fn initialize_base_schemas(schemas: &mut HashMap<String, Value>) {
+        schemas.insert("string".to_string(), json!({ "type": "string" }));
+        schemas.insert("integer".to_string(), json!({ "type": "integer" }));
+        schemas.insert("decimal".to_string(), json!({ "type": "number" }));
+        schemas.insert("boolean".to_string(), json!({ "type": "boolean" }));
+    }
+
+    /// Registers the schema, e.g. `{ "type": "string", "format": "email" }`, used
+    /// for attributes of a custom data type.
+    pub fn register_schema(&mut self, data_type: String, schema: Value) {
+        self.schemas.insert(data_type, schema);
+    }
+
+    /// Exports the category, including the attributes inherited from its parents,
+    /// as a schema of the values `SchemaValidator` accepts for it.
+    pub fn export_category(
+        &self,
+        definition: &Definition,
+        category_id: &String,
+    ) -> Result<Value, Error> {
+        let category = match definition
+            .categories()
+            .into_iter()
+            .find(|category| &category.id == category_id)
+        {
+            Some(category) => category,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidValue,
+                    format!("cannot found category id '{}'", category_id),
+                ))
+            }
+        };
+
+        let attributes = collect_from_definition_and_category(definition, category_id)?;
+
+        let mut properties: Map<String, Value> = Map::new();
+        let mut required: Vec<Value> = vec![
+            Value::String(VALUE_VERSION.to_string()),
+            Value::String(VALUE_TYPE.to_string()),
+        ];
+
+        properties.insert(
+            VALUE_VERSION.to_string(),
+            json!({ "const": definition.version() }),
+        );
+        properties.insert(VALUE_TYPE.to_string(), json!({ "const": category.id }));
+
+        for attribute in attributes {
+            let mut schema = match self.schemas.get(&attribute.data_type) {
+                Some(Value::Object(schema)) => schema.clone(),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::DataTypeNotRegistered,
+                        format!("no schema found for data type '{}'", attribute.data_type),
+                    ))
+                }
+            };
+
+            schema.insert("title".to_string(), Value::String(attribute.name.clone()));
+
+            if let Some(unit) = &attribute.unit {
+                schema.insert(UNIT_KEYWORD.to_string(), Value::String(unit.clone()));
+            }
+
+            if !attribute.optional {
+                required.push(Value::String(attribute.id.clone()));
+            }
+
+            properties.insert(attribute.id, Value::Object(schema));
+        }
+
+        Ok(json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": category.name,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        }))
+    }
+}
+
+impl Default for JsonSchemaExporter {
+    fn default() -> Self {
+        let mut schemas: HashMap<String, Value> = HashMap::new();
+
+        JsonSchemaExporter::initialize_base_schemas(&mut schemas);
+
+        JsonSchemaExporter { schemas }
+    }
+}
+
+/// Non-standard keyword overriding an imported category's id.
+pub const CATEGORY_ID_KEYWORD: &str = "x-category-id";
+
+/// Non-standard keyword overriding whether an imported category is selectable as last.
+pub const SELECTABLE_AS_LAST_KEYWORD: &str = "x-selectable-as-last";
+
+const DEFINITIONS_KEYWORD: &str = "$defs";
+const DEFINITIONS_REFERENCE_PREFIX: &str = "#/$defs/";
+
+const ANNOTATION_KEYWORDS: &[&str] = &["$schema", "$id", "$comment", "title", "description"];
+
+/// Imports JSON Schema (draft 2020-12) documents as definitions.
+///
+/// Every entry of `$defs`, plus the root schema when it declares properties, becomes
+/// a category. Inheritance is expressed through an `allOf` holding a single
+/// `{ "$ref": "#/$defs/<parent>" }`.
+pub struct JsonSchemaImporter {
+    schemas: HashMap<String, Value>,
+}
+
+struct ImportedCategory {
+    pointer: String,
+    source_category: SourceCategory,
+    parent_key: Option<String>,
+    selectable_as_last: Option<bool>,
+}
+
+impl JsonSchemaImporter {
+    /// Registers the schema which identifies attributes of a custom data type.
+    pub fn register_schema(&mut self, data_type: String, schema: Value) {
+        self.schemas.insert(data_type, schema);
+    }
+
+    /// Imports the schema document, failing with every construct which cannot be
+    /// represented by a definition.
+    pub fn import(&self, version: String, schema: &Value) -> Result<Definition, Error> {
+        let mut unsupported: Vec<String> = Vec::new();
+        let mut imported_categories: Vec<(String, ImportedCategory)> = Vec::new();
+
+        let root = match schema.as_object() {
+            Some(root) => root,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::UnsupportedSchema,
+                    "schema document is not an object",
+                ))
+            }
+        };
+
+        match root.get(DEFINITIONS_KEYWORD) {
+            Some(Value::Object(definitions)) => {
+                for (key, category_schema) in definitions {
+                    let pointer = format!("/{}/{}", DEFINITIONS_KEYWORD, key);
+
+                    if let Some(imported_category) =
+                        self.import_category(key, &pointer, category_schema, &mut unsupported)
+                    {
+                        imported_categories.push((key.clone(), imported_category));
+                    }
+                }
+            }
+            Some(_) => unsupported.push(format!("at '/{}': not an object", DEFINITIONS_KEYWORD)),
+            None => (),
+        }
+
+        if root.contains_key("properties") || root.contains_key("allOf") {
+            let key = match root.get("title").and_then(Value::as_str) {
+                Some(title) => title.to_string(),
+                None => String::new(),
+            };
+
+            if let Some(imported_category) =
+                self.import_category(&key, "", schema, &mut unsupported)
+            {
+                imported_categories.push((String::new(), imported_category));
+            }
+        }
+
+        let categories = resolve_categories(imported_categories, &mut unsupported);
+
+        if !unsupported.is_empty() {
+            return Err(Error::new(
+                ErrorKind::UnsupportedSchema,
+                format!("unsupported schema constructs: {}", unsupported.join("; ")),
+            ));
+        }
+
+        let definition = Definition::new(version, categories);
+        let category_index = CategoryIndex::new(&definition);
+
+        for category in definition.categories() {
+            let category_chain = category_index.build_chain(&category.id)?;
+            collect_from_category_chain(&category_index, &category_chain)?;
+        }
+
+        Ok(definition)
+    }
+
+    fn import_category(
+        &self,
+        key: &str,
+        pointer: &str,
+        category_schema: &Value,
+        unsupported: &mut Vec<String>,
+    ) -> Option<ImportedCategory> {
+        let category_schema = match category_schema.as_object() {
+            Some(category_schema) => category_schema,
+            None => {
+                unsupported.push(format!(
+                    "at '{}': category schema is not an object",
+                    pointer
+                ));
+                return None;
+            }
+        };
+
+        let mut properties: Vec<(String, Map<String, Value>)> = Vec::new();
+        let mut required: Vec<String> = Vec::new();
+        let mut parent_key: Option<String> = None;
+        let mut category_id: Option<String> = None;
+        let mut selectable_as_last: Option<bool> = None;
+
+        self.read_object_keywords(
+            pointer,
+            category_schema,
+            &mut properties,
+            &mut required,
+            unsupported,
+        );
+
+        for (keyword, keyword_value) in category_schema {
+            let keyword_pointer = format!("{}/{}", pointer, keyword);
+
+            match keyword.as_str() {
+                DEFINITIONS_KEYWORD if pointer.is_empty() => (),
+                CATEGORY_ID_KEYWORD => match keyword_value.as_str() {
+                    Some(id) => category_id = Some(id.to_string()),
+                    None => unsupported.push(format!("at '{}': not a string", keyword_pointer)),
+                },
+                SELECTABLE_AS_LAST_KEYWORD => match keyword_value.as_bool() {
+                    Some(selectable) => selectable_as_last = Some(selectable),
+                    None => unsupported.push(format!("at '{}': not a boolean", keyword_pointer)),
+                },
+                "allOf" => match keyword_value.as_array() {
+                    Some(subschemas) => {
+                        for (index, subschema) in subschemas.iter().enumerate() {
+                            let subschema_pointer = format!("{}/{}", keyword_pointer, index);
+
+                            match subschema.as_object() {
+                                Some(subschema) if subschema.contains_key("$ref") => {
+                                    let reference = subschema["$ref"].as_str().unwrap_or_default();
+
+                                    if subschema.len() > 1 {
+                                        unsupported.push(format!(
+                                            "at '{}': '$ref' with sibling keywords",
+                                            subschema_pointer
+                                        ));
+                                    } else if !reference.starts_with(DEFINITIONS_REFERENCE_PREFIX) {
+                                        unsupported.push(format!(
+                                            "at '{}': non-local reference '{}'",
+                                            subschema_pointer, reference
+                                        ));
+                                    } else if parent_key.is_some() {
+                                        unsupported.push(format!(
+                                            "at '{}': more than one parent",
+                                            subschema_pointer
+                                        ));
+                                    } else {
+                                        parent_key = Some(
+                                            reference[DEFINITIONS_REFERENCE_PREFIX.len()..]
+                                                .to_string(),
+                                        );
+                                    }
+                                }
+                                Some(subschema) => {
+                                    for (subschema_keyword, _) in subschema {
+                                        if !is_object_keyword(subschema_keyword) {
+                                            unsupported.push(format!(
+                                                "at '{}/{}': unsupported keyword",
+                                                subschema_pointer, subschema_keyword
+                                            ));
+                                        }
+                                    }
+
+                                    self.read_object_keywords(
+                                        &subschema_pointer,
+                                        subschema,
+                                        &mut properties,
+                                        &mut required,
+                                        unsupported,
+                                    );
+                                }
+                                None => unsupported.push(format!(
+                                    "at '{}': subschema is not an object",
+                                    subschema_pointer
+                                )),
+                            }
+                        }
+                    }
+                    None => unsupported.push(format!("at '{}': not an array", keyword_pointer)),
+                },
+                keyword if is_object_keyword(keyword) => (),
+                _ => unsupported.push(format!("at '{}': unsupported keyword", keyword_pointer)),
+            }
+        }
+
+        let mut source_attributes: Vec<SourceAttribute> = Vec::new();
+
+        for (id, property) in properties {
+            if id == VALUE_VERSION || id == VALUE_TYPE {
+                if id == VALUE_TYPE && category_id.is_none() {
+                    category_id = property
+                        .get("const")
+                        .and_then(Value::as_str)
+                        .map(str::to_string);
+                }
+
+                continue;
+            }
+
+            let property_pointer = format!("{}/properties/{}", pointer, id);
+
+            match self.import_attribute(&property_pointer, &id, &property, unsupported) {
+                Some(mut source_attribute) => {
+                    source_attribute.optional = Some(!required.contains(&id));
+                    source_attributes.push(source_attribute);
+                }
+                None => continue,
+            }
+        }
+
+        let category_id = match category_id {
+            Some(category_id) => category_id,
+            None if !key.is_empty() => key.to_string(),
+            None => {
+                unsupported.push(format!(
+                    "at '{}': category has no id, neither '{}' nor a 'type' constant",
+                    pointer, CATEGORY_ID_KEYWORD
+                ));
+                return None;
+            }
+        };
+
+        let name = match category_schema.get("title").and_then(Value::as_str) {
+            Some(title) => title.to_string(),
+            None if !key.is_empty() => key.to_string(),
+            None => category_id.clone(),
+        };
+
+        Some(ImportedCategory {
+            pointer: pointer.to_string(),
+            source_category: SourceCategory {
+                id: Some(category_id),
+                parent: None,
+                parent_name: None,
+                name,
+                selectable_as_last: None,
+                attributes: source_attributes,
+            },
+            parent_key,
+            selectable_as_last,
+        })
+    }
+
+    fn read_object_keywords(
+        &self,
+        pointer: &str,
+        object_schema: &Map<String, Value>,
+        properties: &mut Vec<(String, Map<String, Value>)>,
+        required: &mut Vec<String>,
+        unsupported: &mut Vec<String>,
+    ) {
+        if let Some(schema_type) = object_schema.get("type") {
+            if schema_type != "object" {
+                unsupported.push(format!("at '{}/type': category is not an object", pointer));
+            }
+        }
+
+        if let Some(additional_properties) = object_schema.get("additionalProperties") {
+            if additional_properties != &Value::Bool(true) {
+                unsupported.push(format!(
+                    "at '{}/additionalProperties': extra attributes are always ignored",
+                    pointer
+                ));
+            }
+        }
+
+        match object_schema.get("properties") {
+            Some(Value::Object(object_properties)) => {
+                for (id, property) in object_properties {
+                    match property.as_object() {
+                        Some(property) => properties.push((id.clone(), property.clone())),
+                        None => unsupported.push(format!(
+                            "at '{}/properties/{}': property schema is not an object",
+                            pointer, id
+                        )),
+                    }
+                }
+            }
+            Some(_) => unsupported.push(format!("at '{}/properties': not an object", pointer)),
+            None => (),
+        }
+
+        match object_schema.get("required") {
+            Some(Value::Array(required_ids)) => {
+                for required_id in required_ids {
+                    match required_id.as_str() {
+                        Some(required_id) => required.push(required_id.to_string()),
+                        None => unsupported.push(format!(
+                            "at '{}/required': contains a non-string entry",
+                            pointer
+                        )),
+                    }
+                }
+            }
+            Some(_) => unsupported.push(format!("at '{}/required': not an array", pointer)),
+            None => (),
+        }
+    }
+
+    fn import_attribute(
+        &self,
+        pointer: &str,
+        id: &str,
+        property: &Map<String, Value>,
+        unsupported: &mut Vec<String>,
+    ) -> Option<SourceAttribute> {
+        let mut matched_data_type: Option<(&String, &Map<String, Value>)> = None;
+
+        for (data_type, schema) in &self.schemas {
+            let schema = match schema.as_object() {
+                Some(schema) => schema,
+                None => continue,
+            };
+
+            let matches = schema
+                .iter()
+                .all(|(keyword, keyword_value)| property.get(keyword) == Some(keyword_value));
+            let more_specific = match matched_data_type {
+                Some((_, matched_schema)) => schema.len() > matched_schema.len(),
+                None => true,
+            };
+
+            if matches && more_specific {
+                matched_data_type = Some((data_type, schema));
+            }
+        }
+
+        let (data_type, data_type_schema) = match matched_data_type {
+            Some(matched_data_type) => matched_data_type,
+            None => {
+                unsupported.push(format!(
+                    "at '{}': no registered data type matches the property",
+                    pointer
+                ));
+                return None;
+            }
+        };
+
+        let mut supported = true;
+
+        for keyword in property.keys() {
+            if !data_type_schema.contains_key(keyword)
+                && keyword != UNIT_KEYWORD
+                && !ANNOTATION_KEYWORDS.contains(&keyword.as_str())
+            {
+                unsupported.push(format!("at '{}/{}': unsupported keyword", pointer, keyword));
+                supported = false;
+            }
+        }
+
+        if !supported {
+            return None;
+        }
+
+        Some(SourceAttribute {
+            id: Some(id.to_string()),
+            name: match property.get("title").and_then(Value::as_str) {
+                Some(title) => title.to_string(),
+                None => id.to_string(),
+            },
+            data_type: data_type.clone(),
+            unit: property
+                .get(UNIT_KEYWORD)
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            optional: None,
+        })
+    }
+}
+
+impl Default for JsonSchemaImporter {
+    fn default() -> Self {
+        let mut schemas: HashMap<String, Value> = HashMap::new();
+
+        JsonSchemaExporter::initialize_base_schemas(&mut schemas);
+
+        JsonSchemaImporter { schemas }
+    }
+}
+
+fn is_object_keyword(keyword: &str) -> bool {
+    ANNOTATION_KEYWORDS.contains(&keyword)
+        || matches!(
+            keyword,
+            "type" | "properties" | "required" | "additionalProperties"
+        )
+}
+
+fn resolve_categories(
+    imported_categories: Vec<(String, ImportedCategory)>,
+    unsupported: &mut Vec<String>,
+) -> Vec<ValidatedSourceCategory> {
+    let mut keys: HashMap<String, (String, String)> = HashMap::new();
+    let mut ids: Vec<String> = Vec::new();
+
+    for (key, imported_category) in imported_categories.iter() {
+        let source_category = &imported_category.source_category;
+        let id = source_category.id.clone().unwrap_or_default();
+
+        if ids.contains(&id) {
+            unsupported.push(format!(
+                "at '{}': duplicate category id '{}'",
+                imported_category.pointer, id
+            ));
+        }
+
+        ids.push(id.clone());
+        keys.insert(key.clone(), (id, source_category.name.clone()));
+    }
+
+    let parent_keys: Vec<&String> = imported_categories
+        .iter()
+        .filter_map(|(_, imported_category)| imported_category.parent_key.as_ref())
+        .collect();
+
+    let mut categories: Vec<ValidatedSourceCategory> = Vec::new();
+
+    for (key, imported_category) in imported_categories.iter() {
+        let mut source_category = SourceCategory {
+            id: imported_category.source_category.id.clone(),
+            parent: None,
+            parent_name: None,
+            name: imported_category.source_category.name.clone(),
+            selectable_as_last: imported_category.selectable_as_last,
+            attributes: imported_category.source_category.attributes.clone(),
+        };
+
+        if source_category.selectable_as_last.is_none() {
+            source_category.selectable_as_last = Some(!parent_keys.contains(&key));
+        }
+
+        if let Some(parent_key) = &imported_category.parent_key {
+            match keys.get(parent_key) {
+                Some((parent_id, parent_name)) => {
+                    source_category.parent = Some(parent_id.clone());
+                    source_category.parent_name = Some(parent_name.clone());
+                }
+                None => {
+                    unsupported.push(format!(
+                        "at '{}': reference to unknown category '{}'",
+                        imported_category.pointer, parent_key
+                    ));
+                    continue;
+                }
+            }
+
+            let mut ancestor_key = Some(parent_key);
+            let mut visited_keys: Vec<&String> = vec![key];
+
+            while let Some(current_key) = ancestor_key {
+                if visited_keys.contains(&current_key) {
+                    unsupported.push(format!(
+                        "at '{}': cyclic inheritance",
+                        imported_category.pointer
+                    ));
+                    break;
+                }
+
+                visited_keys.push(current_key);
+                ancestor_key = imported_categories
+                    .iter()
+                    .find(|(candidate_key, _)| candidate_key == current_key)
+                    .and_then(|(_, candidate)| candidate.parent_key.as_ref());
+            }
+        }
+
+        match ValidatedSourceCategory::try_from(source_category) {
+            Ok(category) => categories.push(category),
+            Err(error) => {
+                unsupported.push(format!("at '{}': {}", imported_category.pointer, error))
+            }
+        }
+    }
+
+    categories
+}
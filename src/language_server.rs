@@ -0,0 +1,703 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Read, Write};
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+use serde_json::{json, Value};
+
+use crate::category_attributes::collect_from_definition_and_category;
+use crate::category_chain::build_from_definition_and_category;
+use crate::error::{Error, ErrorKind};
+use crate::json_rpc::{INVALID_REQUEST, JSON_RPC_VERSION, METHOD_NOT_FOUND, PARSE_ERROR};
+use crate::schema_validator::{SchemaValidator, VALUE_TYPE, VALUE_VERSION};
+
+/// Largest message body read, in bytes, larger ones being skipped and answered
+/// with an error.
+pub const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+const CONTENT_LENGTH: &str = "Content-Length";
+const DIAGNOSTIC_SOURCE: &str = "cooplan-definition-schema-validator";
+
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+const DIAGNOSTIC_SEVERITY_ERROR: u8 = 1;
+const COMPLETION_ITEM_KIND_VALUE: u8 = 12;
+const COMPLETION_ITEM_KIND_PROPERTY: u8 = 10;
+const COMPLETION_ITEM_KIND_ENUM_MEMBER: u8 = 20;
+
+/// Language server for JSON files holding one value of a definition, keyed by
+/// attribute ids. Documents are synchronized in full, and the server provides:
+///
+/// - diagnostics of the `SchemaValidator`, published whenever a document is opened
+///   or changed,
+/// - completion of attribute ids, of the `type` categories selectable as last and
+///   of the definition's `version`,
+/// - hover showing an attribute's name, data type and unit, or a category's chain.
+pub struct LanguageServer {
+    definition: Definition,
+    schema_validator: SchemaValidator,
+    documents: HashMap<String, String>,
+    shutdown_requested: bool,
+    exit_requested: bool,
+}
+
+impl LanguageServer {
+    pub fn new(definition: Definition, schema_validator: SchemaValidator) -> LanguageServer {
+        LanguageServer {
+            definition,
+            schema_validator,
+            documents: HashMap::new(),
+            shutdown_requested: false,
+            exit_requested: false,
+        }
+    }
+
+    /// Whether `shutdown` was requested, the server having to exit successfully.
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    /// Answers messages framed by `Content-Length` headers until the input ends or
+    /// `exit` is notified.
+    pub fn serve(&mut self, input: &mut dyn BufRead, output: &mut dyn Write) -> Result<(), Error> {
+        while !self.exit_requested {
+            let outgoing_messages = match read_message(input)? {
+                Some(IncomingMessage::Body(message)) => self.handle_message(&message),
+                Some(IncomingMessage::TooLarge(content_length)) => vec![error_response(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    format!(
+                        "message of {} bytes exceeds {} bytes",
+                        content_length, MAX_MESSAGE_SIZE
+                    ),
+                )],
+                None => break,
+            };
+
+            for outgoing_message in outgoing_messages {
+                write_message(output, &outgoing_message)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Response to the message, if it is a request, and the notifications it causes.
+    pub fn handle_message(&mut self, message: &str) -> Vec<Value> {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(error) => {
+                return vec![error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("failed to parse message: {}", error),
+                )]
+            }
+        };
+
+        let method = match message.get("method").and_then(Value::as_str) {
+            Some(method) => method,
+            // Responses to requests of the client, which the server never sends.
+            None => return Vec::new(),
+        };
+        let params = message.get("params").unwrap_or(&Value::Null);
+
+        match (method, message.get("id").cloned()) {
+            ("initialize", Some(id)) => vec![response(id, self.initialize())],
+            ("shutdown", Some(id)) => {
+                self.shutdown_requested = true;
+
+                vec![response(id, Value::Null)]
+            }
+            ("textDocument/completion", Some(id)) => vec![response(id, self.complete(params))],
+            ("textDocument/hover", Some(id)) => vec![response(id, self.hover(params))],
+            (method, Some(id)) => vec![error_response(
+                id,
+                METHOD_NOT_FOUND,
+                format!("method '{}' not found", method),
+            )],
+            ("exit", None) => {
+                self.exit_requested = true;
+
+                Vec::new()
+            }
+            ("textDocument/didOpen", None) => {
+                let text = params["textDocument"]["text"].as_str();
+
+                self.update_document(params, text)
+            }
+            ("textDocument/didChange", None) => {
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|content_changes| content_changes.last())
+                    .and_then(|content_change| content_change["text"].as_str());
+
+                self.update_document(params, text)
+            }
+            ("textDocument/didClose", None) => match document_uri(params) {
+                Some(uri) => {
+                    self.documents.remove(uri);
+
+                    vec![publish_diagnostics(uri, Vec::new())]
+                }
+                None => Vec::new(),
+            },
+            // Other notifications, like `initialized`, need no answer.
+            _ => Vec::new(),
+        }
+    }
+
+    fn initialize(&self) -> Value {
+        json!({
+            "capabilities": {
+                "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                "completionProvider": { "triggerCharacters": ["\""] },
+                "hoverProvider": true,
+            },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn update_document(&mut self, params: &Value, text: Option<&str>) -> Vec<Value> {
+        let (uri, text) = match (document_uri(params), text) {
+            (Some(uri), Some(text)) => (uri.to_string(), text.to_string()),
+            _ => return Vec::new(),
+        };
+
+        let diagnostics = self.diagnose(&text);
+        self.documents.insert(uri.clone(), text);
+
+        vec![publish_diagnostics(&uri, diagnostics)]
+    }
+
+    /// Diagnostic of the document's validation error, covering the error's span or,
+    /// when it has none, the start of the document.
+    fn diagnose(&mut self, text: &str) -> Vec<Value> {
+        let error = match self
+            .schema_validator
+            .validate(text.to_string(), self.definition.clone())
+        {
+            Ok(_) => return Vec::new(),
+            Err(error) => error,
+        };
+
+        let (start, end) = match error.span {
+            Some(span) => (span.start.offset, span.end.offset),
+            None => (0, 0),
+        };
+
+        vec![json!({
+            "range": range(text, start, end),
+            "severity": DIAGNOSTIC_SEVERITY_ERROR,
+            "code": format!("{:?}", error.kind()),
+            "source": DIAGNOSTIC_SOURCE,
+            "message": error.message,
+        })]
+    }
+
+    fn complete(&self, params: &Value) -> Value {
+        let (text, offset) = match self.document_position(params) {
+            Some(document_position) => document_position,
+            None => return json!([]),
+        };
+
+        let scan = scan_strings(&text[..offset]);
+        let (depth, role, in_string) = match scan.tokens.last() {
+            Some(token) if !token.terminated => (token.depth, token.role.clone(), true),
+            _ => match scan.next {
+                Some((depth, role)) => (depth, role, false),
+                None => return json!([]),
+            },
+        };
+
+        if depth != 1 {
+            return json!([]);
+        }
+
+        let items: Vec<Value> = match role {
+            TokenRole::Key => self
+                .document_attributes(text)
+                .iter()
+                .map(|attribute| {
+                    completion_item(
+                        &attribute.id,
+                        COMPLETION_ITEM_KIND_PROPERTY,
+                        &attribute.name,
+                        describe_attribute(attribute),
+                        in_string,
+                    )
+                })
+                .collect(),
+            TokenRole::Value(Some(key)) if key == VALUE_TYPE => self
+                .definition
+                .categories()
+                .into_iter()
+                .filter(|category| category.selectable_as_last)
+                .map(|category| {
+                    completion_item(
+                        &category.id,
+                        COMPLETION_ITEM_KIND_ENUM_MEMBER,
+                        &category.name,
+                        self.describe_category(&category.id),
+                        in_string,
+                    )
+                })
+                .collect(),
+            TokenRole::Value(Some(key)) if key == VALUE_VERSION => vec![completion_item(
+                &self.definition.version(),
+                COMPLETION_ITEM_KIND_VALUE,
+                "definition version",
+                String::new(),
+                in_string,
+            )],
+            TokenRole::Value(_) => Vec::new(),
+        };
+
+        json!(items)
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let (text, offset) = match self.document_position(params) {
+            Some(document_position) => document_position,
+            None => return Value::Null,
+        };
+
+        let token = match scan_strings(text)
+            .tokens
+            .into_iter()
+            .find(|token| token.depth == 1 && token.start <= offset && offset < token.end)
+        {
+            Some(token) => token,
+            None => return Value::Null,
+        };
+
+        let contents = match &token.role {
+            TokenRole::Value(Some(key)) if key == VALUE_TYPE => self
+                .definition
+                .categories()
+                .into_iter()
+                .find(|category| category.id == token.content)
+                .map(|category| {
+                    format!(
+                        "**{}**: {}",
+                        category.name,
+                        self.describe_category(&category.id)
+                    )
+                }),
+            TokenRole::Key => self.find_attribute(&token.content),
+            TokenRole::Value(Some(key)) => self.find_attribute(key),
+            TokenRole::Value(None) => None,
+        };
+
+        match contents {
+            Some(contents) => json!({
+                "contents": { "kind": "markdown", "value": contents },
+                "range": range(text, token.start, token.end),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    /// Text of the params' document and byte offset of their position within it.
+    fn document_position(&self, params: &Value) -> Option<(&str, usize)> {
+        let text = self.documents.get(document_uri(params)?)?;
+        let line = params["position"]["line"].as_u64()?;
+        let character = params["position"]["character"].as_u64()?;
+
+        Some((
+            text.as_str(),
+            position_to_offset(text, line as usize, character as usize),
+        ))
+    }
+
+    /// Attributes of the document's category or, while it is unknown, every
+    /// attribute of the definition.
+    fn document_attributes(&self, text: &str) -> Vec<ValidatedSourceAttribute> {
+        let category_id = scan_strings(text)
+            .tokens
+            .into_iter()
+            .find(|token| {
+                token.depth == 1
+                    && token.terminated
+                    && token.role == TokenRole::Value(Some(VALUE_TYPE.to_string()))
+            })
+            .map(|token| token.content);
+
+        if let Some(category_id) = category_id {
+            if let Ok(attributes) =
+                collect_from_definition_and_category(&self.definition, &category_id)
+            {
+                return attributes;
+            }
+        }
+
+        let mut attributes: Vec<ValidatedSourceAttribute> = Vec::new();
+
+        for category in self.definition.categories() {
+            for attribute in category.attributes {
+                if !attributes
+                    .iter()
+                    .any(|collected_attribute| collected_attribute.id == attribute.id)
+                {
+                    attributes.push(attribute);
+                }
+            }
+        }
+
+        attributes
+    }
+
+    fn find_attribute(&self, attribute_id: &str) -> Option<String> {
+        self.definition
+            .categories()
+            .into_iter()
+            .flat_map(|category| category.attributes)
+            .find(|attribute| attribute.id == attribute_id)
+            .map(|attribute| format!("**{}**: {}", attribute.name, describe_attribute(&attribute)))
+    }
+
+    fn describe_category(&self, category_id: &String) -> String {
+        match build_from_definition_and_category(&self.definition, category_id) {
+            Ok(category_chain) => format!(
+                "category `{}`",
+                category_chain
+                    .names()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(" > ")
+            ),
+            Err(error) => error.message,
+        }
+    }
+}
+
+/// Role of a string within the JSON text.
+#[derive(Debug, Clone, PartialEq)]
+enum TokenRole {
+    Key,
+    /// Value, of the key if it belongs to an object.
+    Value(Option<String>),
+}
+
+/// String of the JSON text, its offsets including the quotes.
+struct StringToken {
+    start: usize,
+    end: usize,
+    content: String,
+    /// Number of objects and arrays enclosing the string.
+    depth: usize,
+    role: TokenRole,
+    /// Whether the string was closed before the text ended.
+    terminated: bool,
+}
+
+struct Scan {
+    tokens: Vec<StringToken>,
+    /// Depth and role of a string starting where the text ends, if one may.
+    next: Option<(usize, TokenRole)>,
+}
+
+#[derive(PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+/// Finds the strings of a JSON text, tolerating the incomplete and malformed
+/// texts of documents being edited.
+fn scan_strings(text: &str) -> Scan {
+    let mut tokens: Vec<StringToken> = Vec::new();
+    // Enclosing containers, each with the last key read within it.
+    let mut containers: Vec<(Container, Option<String>)> = Vec::new();
+    let mut after_colon = false;
+    let mut value_read = false;
+    let mut characters = text.char_indices();
+
+    while let Some((index, character)) = characters.next() {
+        match character {
+            '"' => {
+                let role = match containers.last() {
+                    Some((Container::Object, _)) if !after_colon => TokenRole::Key,
+                    Some((Container::Object, key)) => TokenRole::Value(key.clone()),
+                    _ => TokenRole::Value(None),
+                };
+
+                let mut content = String::new();
+                let mut end: Option<usize> = None;
+
+                while let Some((string_index, string_character)) = characters.next() {
+                    match string_character {
+                        '"' => {
+                            end = Some(string_index + 1);
+                            break;
+                        }
+                        '\\' => {
+                            if let Some((_, escaped_character)) = characters.next() {
+                                content.push(escaped_character);
+                            }
+                        }
+                        _ => content.push(string_character),
+                    }
+                }
+
+                match (&role, containers.last_mut()) {
+                    (TokenRole::Key, Some((_, key))) => *key = Some(content.clone()),
+                    _ => value_read = true,
+                }
+
+                tokens.push(StringToken {
+                    start: index,
+                    end: end.unwrap_or(text.len()),
+                    content,
+                    depth: containers.len(),
+                    role,
+                    terminated: end.is_some(),
+                });
+            }
+            '{' | '[' => {
+                let container = match character {
+                    '{' => Container::Object,
+                    _ => Container::Array,
+                };
+
+                containers.push((container, None));
+                after_colon = false;
+                value_read = false;
+            }
+            '}' | ']' => {
+                containers.pop();
+                value_read = true;
+            }
+            ':' => {
+                after_colon = true;
+                value_read = false;
+            }
+            ',' => {
+                after_colon = false;
+                value_read = false;
+            }
+            character if character.is_whitespace() => (),
+            _ => value_read = true,
+        }
+    }
+
+    let next = match containers.last() {
+        _ if value_read => None,
+        Some((Container::Object, _)) if !after_colon => Some(TokenRole::Key),
+        Some((Container::Object, key)) => Some(TokenRole::Value(key.clone())),
+        Some((Container::Array, _)) => Some(TokenRole::Value(None)),
+        None => None,
+    };
+
+    Scan {
+        tokens,
+        next: next.map(|role| (containers.len(), role)),
+    }
+}
+
+fn describe_attribute(attribute: &ValidatedSourceAttribute) -> String {
+    let mut description = format!("`{}` (`{}`)", attribute.data_type, attribute.id);
+
+    if let Some(unit) = &attribute.unit {
+        description.push_str(&format!(" in {}", unit));
+    }
+
+    if attribute.optional {
+        description.push_str(", optional");
+    }
+
+    description
+}
+
+fn completion_item(
+    label: &str,
+    kind: u8,
+    detail: &str,
+    documentation: String,
+    in_string: bool,
+) -> Value {
+    let insert_text = if in_string {
+        label.to_string()
+    } else {
+        format!("\"{}\"", label)
+    };
+
+    json!({
+        "label": label,
+        "kind": kind,
+        "detail": detail,
+        "documentation": { "kind": "markdown", "value": documentation },
+        "insertText": insert_text,
+    })
+}
+
+fn document_uri(params: &Value) -> Option<&str> {
+    params["textDocument"]["uri"].as_str()
+}
+
+/// Byte offset of the position, its character counting UTF-16 code units.
+fn position_to_offset(text: &str, line: usize, character: usize) -> usize {
+    let mut line_start = 0;
+
+    for _ in 0..line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut code_units = 0;
+
+    for (index, line_character) in text[line_start..].char_indices() {
+        if line_character == '\n' || code_units >= character {
+            return line_start + index;
+        }
+
+        code_units += line_character.len_utf16();
+    }
+
+    text.len()
+}
+
+fn offset_to_position(text: &str, offset: usize) -> Value {
+    let prefix = &text[..offset];
+    let line_start = prefix.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+    json!({
+        "line": prefix.matches('\n').count(),
+        "character": prefix[line_start..].encode_utf16().count(),
+    })
+}
+
+fn range(text: &str, start: usize, end: usize) -> Value {
+    json!({
+        "start": offset_to_position(text, start),
+        "end": offset_to_position(text, end),
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": JSON_RPC_VERSION,
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": JSON_RPC_VERSION, "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": JSON_RPC_VERSION,
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Message read from the input.
+enum IncomingMessage {
+    Body(String),
+    /// Message skipped for its body's length exceeding [`MAX_MESSAGE_SIZE`].
+    TooLarge(usize),
+}
+
+/// Next message, none once the input ends.
+fn read_message(input: &mut dyn BufRead) -> Result<Option<IncomingMessage>, Error> {
+    let mut content_length: Option<usize> = None;
+    let mut header = String::new();
+
+    let content_length = loop {
+        header.clear();
+
+        match input.read_line(&mut header) {
+            Ok(0) => return Ok(None),
+            Ok(_) => (),
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::IoFailure,
+                    format!("failed to read message header: {}", error),
+                ))
+            }
+        }
+
+        let header = header.trim();
+
+        if header.is_empty() {
+            match content_length {
+                Some(content_length) => break content_length,
+                None => continue,
+            }
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case(CONTENT_LENGTH) {
+                content_length = match value.trim().parse() {
+                    Ok(content_length) => Some(content_length),
+                    Err(_) => {
+                        return Err(Error::new(
+                            ErrorKind::DeserializationFailure,
+                            format!("invalid {} header '{}'", CONTENT_LENGTH, header),
+                        ))
+                    }
+                };
+            }
+        }
+    };
+
+    if content_length > MAX_MESSAGE_SIZE {
+        let skipped = io::copy(
+            &mut (&mut *input).take(content_length as u64),
+            &mut io::sink(),
+        );
+
+        return match skipped {
+            Ok(skipped) if skipped == content_length as u64 => {
+                Ok(Some(IncomingMessage::TooLarge(content_length)))
+            }
+            Ok(_) => Err(Error::new(
+                ErrorKind::IoFailure,
+                "failed to read message body: input ended",
+            )),
+            Err(error) => Err(Error::new(
+                ErrorKind::IoFailure,
+                format!("failed to read message body: {}", error),
+            )),
+        };
+    }
+
+    let mut body = vec![0; content_length];
+
+    if let Err(error) = input.read_exact(&mut body) {
+        return Err(Error::new(
+            ErrorKind::IoFailure,
+            format!("failed to read message body: {}", error),
+        ));
+    }
+
+    match String::from_utf8(body) {
+        Ok(body) => Ok(Some(IncomingMessage::Body(body))),
+        Err(error) => Err(Error::new(
+            ErrorKind::DeserializationFailure,
+            format!("message body is not UTF-8: {}", error),
+        )),
+    }
+}
+
+fn write_message(output: &mut dyn Write, message: &Value) -> Result<(), Error> {
+    let body = message.to_string();
+    let written = write!(output, "{}: {}\r\n\r\n{}", CONTENT_LENGTH, body.len(), body)
+        .and_then(|_| output.flush());
+
+    match written {
+        Ok(_) => Ok(()),
+        Err(error) => Err(Error::new(
+            ErrorKind::IoFailure,
+            format!("failed to write message: {}", error),
+        )),
+    }
+}
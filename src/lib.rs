@@ -1,6 +1,24 @@
+pub mod category_attributes;
 pub mod category_chain;
+pub mod cli;
+pub mod codegen;
+pub mod definition_diff;
+pub mod definition_registry;
 pub mod definition_type;
 pub mod definition_value;
+pub mod diagnostic;
 pub mod error;
+pub mod http_service;
+pub mod json_rpc;
+pub mod json_schema;
+pub mod language_server;
+pub mod lint;
+pub mod migration;
+pub mod mutation;
+pub mod report;
+pub mod sample;
 pub mod schema_validator;
+pub mod source_map;
 pub mod validations;
+pub mod value_store;
+pub mod version_policy;
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use crate::category_attributes::collect_from_category_chain;
+use crate::category_chain::CategoryIndex;
+use crate::error::ErrorKind;
+use crate::schema_validator::{VALUE_TYPE, VALUE_VERSION};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Values of the definition may be validated wrongly, or not at all.
+    Error,
+    /// The definition works, but is most likely not what its author intended.
+    Warning,
+}
+
+/// Problem found within a definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub severity: Severity,
+    /// Kind of the error the first affected value would fail with.
+    pub kind: ErrorKind,
+    pub category_id: Option<String>,
+    pub attribute_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &LintIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &LintIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        kind: ErrorKind,
+        category_id: &str,
+        attribute_id: Option<&str>,
+        message: String,
+    ) {
+        self.issues.push(LintIssue {
+            severity,
+            kind,
+            category_id: Some(category_id.to_string()),
+            attribute_id: attribute_id.map(str::to_string),
+            message,
+        });
+    }
+}
+
+/// Checks the whole definition, `is_registered` telling whether a data type has a
+/// registered validation. See `SchemaValidator::lint`.
+pub fn lint_definition(
+    definition: &Definition,
+    is_registered: impl Fn(&str) -> bool,
+) -> LintReport {
+    let mut report = LintReport::default();
+    let category_index = CategoryIndex::new(definition);
+    let categories = definition.categories();
+
+    let mut category_ids: HashSet<&String> = HashSet::new();
+    let mut parent_ids: HashSet<&String> = HashSet::new();
+
+    for category in categories.as_slice() {
+        if !category_ids.insert(&category.id) {
+            report.push(
+                Severity::Error,
+                ErrorKind::InvalidDefinition,
+                &category.id,
+                None,
+                format!("category id '{}' is declared more than once", category.id),
+            );
+        }
+
+        if let Some(parent) = &category.parent {
+            parent_ids.insert(parent);
+        }
+    }
+
+    for category in categories.as_slice() {
+        for attribute in category.attributes.as_slice() {
+            if attribute.id == VALUE_VERSION || attribute.id == VALUE_TYPE {
+                report.push(
+                    Severity::Error,
+                    ErrorKind::InvalidDefinition,
+                    &category.id,
+                    Some(&attribute.id),
+                    format!(
+                        "attribute id '{}' collides with a reserved value key",
+                        attribute.id
+                    ),
+                );
+            }
+
+            if !is_registered(&attribute.data_type) {
+                report.push(
+                    Severity::Error,
+                    ErrorKind::ValidationNotRegistered,
+                    &category.id,
+                    Some(&attribute.id),
+                    format!(
+                        "no validation found for data type '{}' of attribute id '{}'",
+                        attribute.data_type, attribute.id
+                    ),
+                );
+            }
+        }
+
+        // Duplicated attributes and conflicting overrides are reported by the chain's
+        // resolution, which also fails for categories unreachable from a root.
+        let attributes = category_index
+            .build_chain(&category.id)
+            .and_then(|category_chain| {
+                collect_from_category_chain(&category_index, &category_chain)
+            });
+
+        // Categories sharing a faulty ancestor fail with the same message.
+        if let Err(error) = attributes {
+            if !report
+                .issues
+                .iter()
+                .any(|issue| issue.message == error.message)
+            {
+                report.push(
+                    Severity::Error,
+                    error.kind(),
+                    &category.id,
+                    error.attribute_id.as_deref(),
+                    error.message,
+                );
+            }
+        }
+
+        if !category.selectable_as_last && !parent_ids.contains(&category.id) {
+            report.push(
+                Severity::Warning,
+                ErrorKind::InvalidValue,
+                &category.id,
+                None,
+                format!(
+                    "category id '{}' has no child categories and is not selectable as last, no value can use it",
+                    category.id
+                ),
+            );
+        }
+    }
+
+    report
+}
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, VecDeque};
+
+use cooplan_definitions_lib::definition::Definition;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::definition_value::DefinitionValue;
+use crate::error::{Error, ErrorKind};
+use crate::schema_validator::{SchemaValidator, VALUE_TYPE, VALUE_VERSION};
+
+/// Declarative change applied to a value when migrating it between two versions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MigrationStep {
+    /// Moves the attribute's value to a new attribute id.
+    RenameAttribute { from: String, to: String },
+    /// Sets the attribute's value when the value lacks it.
+    SetDefault { attribute_id: String, value: Value },
+    /// Removes the attribute from the value.
+    DropAttribute { attribute_id: String },
+    /// Converts the attribute's value into one of the base data types: `string`,
+    /// `integer`, `decimal` or `boolean`.
+    ConvertType {
+        attribute_id: String,
+        data_type: String,
+    },
+    /// Moves values of a category into another category.
+    ReparentCategory { from: String, to: String },
+}
+
+/// Steps migrating values from one definition version to another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Migration {
+    pub from_version: String,
+    pub to_version: String,
+    pub steps: Vec<MigrationStep>,
+}
+
+/// Step which modified the migrated value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationChange {
+    pub from_version: String,
+    pub to_version: String,
+    pub step: MigrationStep,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    /// Versions the value went through, starting with its original version.
+    pub versions: Vec<String>,
+    pub changes: Vec<MigrationChange>,
+}
+
+/// Migrates values across definition versions by chaining registered migrations.
+#[derive(Default)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+}
+
+impl Migrator {
+    pub fn register_migration(&mut self, migration: Migration) {
+        self.migrations.push(migration);
+    }
+
+    pub fn migrate(
+        &self,
+        value: String,
+        schema_validator: &mut SchemaValidator,
+        definition: Definition,
+    ) -> Result<(DefinitionValue, MigrationReport), Error> {
+        match serde_json::from_str(value.as_str()) {
+            Ok::<Map<String, Value>, _>(object) => {
+                self.migrate_object(object, schema_validator, definition)
+            }
+            Err(error) => Err(Error::new(
+                ErrorKind::DeserializationFailure,
+                format!("failed to deserialize value: {}", error),
+            )),
+        }
+    }
+
+    /// Migrates the value up to the definition's version and validates the result
+    /// against the definition.
+    pub fn migrate_object(
+        &self,
+        mut object: Map<String, Value>,
+        schema_validator: &mut SchemaValidator,
+        definition: Definition,
+    ) -> Result<(DefinitionValue, MigrationReport), Error> {
+        let value_version = schema_validator.try_get_version(&object)?;
+
+        let migrations = self.find_migrations(&value_version, &definition.version())?;
+
+        let mut report = MigrationReport {
+            versions: vec![value_version],
+            changes: Vec::new(),
+        };
+
+        for migration in migrations {
+            for step in migration.steps.as_slice() {
+                if let Some(description) = apply_step(&mut object, step)? {
+                    report.changes.push(MigrationChange {
+                        from_version: migration.from_version.clone(),
+                        to_version: migration.to_version.clone(),
+                        step: step.clone(),
+                        description,
+                    });
+                }
+            }
+
+            report.versions.push(migration.to_version.clone());
+        }
+
+        if report.versions.len() > 1 {
+            object.insert(
+                VALUE_VERSION.to_string(),
+                Value::String(definition.version()),
+            );
+        }
+
+        let definition_value = schema_validator.validate_object(object, definition)?;
+
+        Ok((definition_value, report))
+    }
+
+    /// Finds the shortest chain of migrations between both versions.
+    fn find_migrations(
+        &self,
+        from_version: &str,
+        to_version: &str,
+    ) -> Result<Vec<&Migration>, Error> {
+        let from_version = from_version.to_lowercase();
+        let to_version = to_version.to_lowercase();
+
+        let mut previous: HashMap<String, &Migration> = HashMap::new();
+        let mut pending_versions: VecDeque<String> = VecDeque::from([from_version.clone()]);
+
+        while let Some(version) = pending_versions.pop_front() {
+            if version == to_version {
+                break;
+            }
+
+            for migration in self.migrations.as_slice() {
+                let next_version = migration.to_version.to_lowercase();
+
+                if migration.from_version.to_lowercase() == version
+                    && next_version != from_version
+                    && !previous.contains_key(&next_version)
+                {
+                    previous.insert(next_version.clone(), migration);
+                    pending_versions.push_back(next_version);
+                }
+            }
+        }
+
+        let mut migrations: Vec<&Migration> = Vec::new();
+        let mut version = to_version.clone();
+
+        while version != from_version {
+            match previous.get(&version) {
+                Some(migration) => {
+                    version = migration.from_version.to_lowercase();
+                    migrations.push(migration);
+                }
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::MigrationNotFound,
+                        format!(
+                            "no migration found from version '{}' to version '{}'",
+                            from_version, to_version
+                        ),
+                    ))
+                }
+            }
+        }
+
+        migrations.reverse();
+
+        Ok(migrations)
+    }
+}
+
+fn apply_step(
+    object: &mut Map<String, Value>,
+    step: &MigrationStep,
+) -> Result<Option<String>, Error> {
+    match step {
+        MigrationStep::RenameAttribute { from, to } => match object.remove(from) {
+            Some(attribute_value) => {
+                object.insert(to.clone(), attribute_value);
+
+                Ok(Some(format!("renamed attribute '{}' to '{}'", from, to)))
+            }
+            None => Ok(None),
+        },
+        MigrationStep::SetDefault {
+            attribute_id,
+            value,
+        } => {
+            if object.contains_key(attribute_id) {
+                return Ok(None);
+            }
+
+            object.insert(attribute_id.clone(), value.clone());
+
+            Ok(Some(format!(
+                "set attribute '{}' to default value '{}'",
+                attribute_id, value
+            )))
+        }
+        MigrationStep::DropAttribute { attribute_id } => match object.remove(attribute_id) {
+            Some(_) => Ok(Some(format!("dropped attribute '{}'", attribute_id))),
+            None => Ok(None),
+        },
+        MigrationStep::ConvertType {
+            attribute_id,
+            data_type,
+        } => {
+            let attribute_value = match object.get(attribute_id) {
+                Some(attribute_value) => attribute_value,
+                None => return Ok(None),
+            };
+
+            let converted_value = convert_value(attribute_value, data_type).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::MigrationFailure,
+                    format!(
+                        "failed to convert attribute '{}' value '{}' to '{}'",
+                        attribute_id, attribute_value, data_type
+                    ),
+                )
+            })?;
+
+            if &converted_value == attribute_value {
+                return Ok(None);
+            }
+
+            let description = format!(
+                "converted attribute '{}' from '{}' to '{}'",
+                attribute_id, attribute_value, converted_value
+            );
+            object.insert(attribute_id.clone(), converted_value);
+
+            Ok(Some(description))
+        }
+        MigrationStep::ReparentCategory { from, to } => match object.get(VALUE_TYPE) {
+            Some(Value::String(value_type)) if value_type == from => {
+                object.insert(VALUE_TYPE.to_string(), Value::String(to.clone()));
+
+                Ok(Some(format!(
+                    "moved value from category '{}' to '{}'",
+                    from, to
+                )))
+            }
+            _ => Ok(None),
+        },
+    }
+}
+
+fn convert_value(attribute_value: &Value, data_type: &str) -> Option<Value> {
+    match (data_type, attribute_value) {
+        ("string", Value::String(_)) => Some(attribute_value.clone()),
+        ("string", Value::Number(number)) => Some(Value::String(number.to_string())),
+        ("string", Value::Bool(boolean)) => Some(Value::String(boolean.to_string())),
+        ("integer", Value::Number(number)) => match number.as_i64() {
+            Some(integer) => Some(Value::Number(Number::from(integer))),
+            None => number
+                .as_f64()
+                .filter(|decimal| {
+                    decimal.fract() == 0.0
+                        && *decimal >= i64::MIN as f64
+                        && *decimal < i64::MAX as f64
+                })
+                .map(|decimal| Value::Number(Number::from(decimal as i64))),
+        },
+        ("integer", Value::String(string)) => string
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .map(|integer| Value::Number(Number::from(integer))),
+        ("integer", Value::Bool(boolean)) => Some(Value::Number(Number::from(*boolean as i64))),
+        ("decimal", Value::Number(_)) => Some(attribute_value.clone()),
+        ("decimal", Value::String(string)) => string
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        ("boolean", Value::Bool(_)) => Some(attribute_value.clone()),
+        ("boolean", Value::String(string)) => match string.trim().to_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("boolean", Value::Number(number)) => match number.as_i64() {
+            Some(0) => Some(Value::Bool(false)),
+            Some(1) => Some(Value::Bool(true)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
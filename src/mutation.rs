@@ -0,0 +1,282 @@
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::{Map, Number, Value};
+
+use crate::category_attributes::collect_from_category_chain;
+use crate::category_chain::CategoryIndex;
+use crate::definition_type::{DefinitionType, DefinitionTypeMarkers};
+use crate::error::{Error, ErrorKind};
+use crate::schema_validator::{VALUE_TYPE, VALUE_VERSION};
+use crate::version_policy::VersionPolicy;
+
+/// Rule broken by a mutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MutationKind {
+    RemoveVersion,
+    RetypeVersion,
+    /// Sets a version the version policy does not accept.
+    ChangeVersion,
+    RemoveType,
+    RetypeType,
+    /// Sets a category id unknown to the definition.
+    UnknownType,
+    RemoveAttribute {
+        attribute_id: String,
+    },
+    /// Sets a value of another JSON type than the attribute's data type.
+    RetypeAttribute {
+        attribute_id: String,
+    },
+    /// Removes the only marker of the value's definition type.
+    RemoveMarker {
+        attribute_id: String,
+    },
+    FalseMarker {
+        attribute_id: String,
+    },
+    /// Sets a marker of another definition type.
+    ConflictingMarker {
+        attribute_id: String,
+    },
+}
+
+/// Invalid variant of a valid value, breaking exactly one rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mutation {
+    pub kind: MutationKind,
+    /// Kind of the error `SchemaValidator` is expected to fail with.
+    pub expected_error: ErrorKind,
+    pub value: Map<String, Value>,
+}
+
+/// Derives invalid variants from valid values, for testing how consumers handle
+/// validation failures.
+///
+/// The expected errors assume a `SchemaValidator` configured alike, detecting the
+/// definition type from markers only. Attributes carry no constraints besides their
+/// data type, hence retyping an attribute is the only way of breaking it.
+#[derive(Debug, Clone, Default)]
+pub struct ValueMutator {
+    version_policy: VersionPolicy,
+    definition_type_markers: DefinitionTypeMarkers,
+}
+
+impl ValueMutator {
+    /// Version policy of the validator, so changed versions are rejected by it.
+    pub fn set_version_policy(&mut self, version_policy: VersionPolicy) {
+        self.version_policy = version_policy;
+    }
+
+    pub fn set_definition_type_markers(&mut self, definition_type_markers: DefinitionTypeMarkers) {
+        self.definition_type_markers = definition_type_markers;
+    }
+
+    /// Mutations of the value, which must be valid for the definition.
+    pub fn mutate(
+        &self,
+        definition: &Definition,
+        value: &Map<String, Value>,
+    ) -> Result<Vec<Mutation>, Error> {
+        let value_type = match value.get(VALUE_TYPE) {
+            Some(Value::String(value_type)) => value_type.clone(),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidValue,
+                    "type attribute is missing or is not a string",
+                ))
+            }
+        };
+
+        let category_index = CategoryIndex::new(definition);
+        let category_chain = category_index.build_chain(&value_type)?;
+        let attributes = collect_from_category_chain(&category_index, &category_chain)?;
+
+        let mut mutations: Vec<Mutation> = Vec::new();
+
+        let mut push =
+            |kind: MutationKind, expected_error: ErrorKind, value: Map<String, Value>| {
+                mutations.push(Mutation {
+                    kind,
+                    expected_error,
+                    value,
+                })
+            };
+
+        push(
+            MutationKind::RemoveVersion,
+            ErrorKind::VersionAttributeMissing,
+            without(value, VALUE_VERSION),
+        );
+        push(
+            MutationKind::RetypeVersion,
+            ErrorKind::InvalidValue,
+            with(value, VALUE_VERSION, Value::Number(Number::from(0))),
+        );
+
+        if let Some(version) = self.incompatible_version(&definition.version()) {
+            push(
+                MutationKind::ChangeVersion,
+                ErrorKind::ValueDefinitionMismatch,
+                with(value, VALUE_VERSION, Value::String(version)),
+            );
+        }
+
+        push(
+            MutationKind::RemoveType,
+            ErrorKind::TypeAttributeMissing,
+            without(value, VALUE_TYPE),
+        );
+        push(
+            MutationKind::RetypeType,
+            ErrorKind::InvalidValue,
+            with(value, VALUE_TYPE, Value::Number(Number::from(0))),
+        );
+        push(
+            MutationKind::UnknownType,
+            ErrorKind::InvalidValue,
+            with(
+                value,
+                VALUE_TYPE,
+                Value::String(unknown_category_id(&category_index, &value_type)),
+            ),
+        );
+
+        let set_markers: Vec<(&String, &DefinitionType)> = self
+            .definition_type_markers
+            .markers()
+            .filter(|(attribute_id, _)| {
+                attributes
+                    .iter()
+                    .any(|attribute| &&attribute.id == attribute_id)
+                    && value.get(attribute_id.as_str()) == Some(&Value::Bool(true))
+            })
+            .collect();
+
+        for attribute in attributes.as_slice() {
+            let attribute_value = match value.get(&attribute.id) {
+                Some(attribute_value) => attribute_value,
+                None => {
+                    if let Some(marker_definition_type) = self.marker_definition_type(&attribute.id)
+                    {
+                        if set_markers
+                            .iter()
+                            .any(|(_, definition_type)| definition_type != &marker_definition_type)
+                        {
+                            push(
+                                MutationKind::ConflictingMarker {
+                                    attribute_id: attribute.id.clone(),
+                                },
+                                ErrorKind::DefinitionTypeConflict,
+                                with(value, &attribute.id, Value::Bool(true)),
+                            );
+                        }
+                    }
+
+                    continue;
+                }
+            };
+
+            if !attribute.optional {
+                push(
+                    MutationKind::RemoveAttribute {
+                        attribute_id: attribute.id.clone(),
+                    },
+                    ErrorKind::InvalidValue,
+                    without(value, &attribute.id),
+                );
+            }
+
+            push(
+                MutationKind::RetypeAttribute {
+                    attribute_id: attribute.id.clone(),
+                },
+                ErrorKind::InvalidValue,
+                with(
+                    value,
+                    &attribute.id,
+                    retyped_value(&attribute.data_type, attribute_value),
+                ),
+            );
+
+            if !set_markers
+                .iter()
+                .any(|(attribute_id, _)| *attribute_id == &attribute.id)
+            {
+                continue;
+            }
+
+            if attribute.optional && set_markers.len() == 1 {
+                push(
+                    MutationKind::RemoveMarker {
+                        attribute_id: attribute.id.clone(),
+                    },
+                    ErrorKind::InvalidValue,
+                    without(value, &attribute.id),
+                );
+            }
+
+            push(
+                MutationKind::FalseMarker {
+                    attribute_id: attribute.id.clone(),
+                },
+                ErrorKind::InvalidValue,
+                with(value, &attribute.id, Value::Bool(false)),
+            );
+        }
+
+        Ok(mutations)
+    }
+
+    fn incompatible_version(&self, definition_version: &str) -> Option<String> {
+        [
+            format!("mutated-{}", definition_version),
+            format!("{}-mutated", definition_version),
+        ]
+        .into_iter()
+        .find(|version| {
+            !self
+                .version_policy
+                .is_compatible(definition_version, version)
+        })
+    }
+
+    fn marker_definition_type(&self, attribute_id: &str) -> Option<&DefinitionType> {
+        self.definition_type_markers
+            .markers()
+            .find(|(marker_attribute_id, _)| marker_attribute_id.as_str() == attribute_id)
+            .map(|(_, definition_type)| definition_type)
+    }
+}
+
+fn with(value: &Map<String, Value>, key: &str, attribute_value: Value) -> Map<String, Value> {
+    let mut mutated_value = value.clone();
+    mutated_value.insert(key.to_string(), attribute_value);
+
+    mutated_value
+}
+
+fn without(value: &Map<String, Value>, key: &str) -> Map<String, Value> {
+    let mut mutated_value = value.clone();
+    mutated_value.remove(key);
+
+    mutated_value
+}
+
+fn unknown_category_id(category_index: &CategoryIndex, category_id: &str) -> String {
+    let mut unknown_category_id = format!("{}-mutated", category_id);
+
+    while category_index.get(&unknown_category_id).is_some() {
+        unknown_category_id.push_str("-mutated");
+    }
+
+    unknown_category_id
+}
+
+/// Value of another JSON type, rejected by the base validations. Custom data types
+/// are expected to reject `null`.
+fn retyped_value(data_type: &str, attribute_value: &Value) -> Value {
+    match data_type {
+        "string" => Value::Number(Number::from(0)),
+        "integer" | "decimal" | "boolean" => Value::String(attribute_value.to_string()),
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,292 @@
+use std::env;
+
+use serde_json::{json, Map, Value};
+
+use crate::definition_value::DefinitionValue;
+use crate::error::Error;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Source of the values read from stdin.
+pub const STDIN_SOURCE: &str = "<stdin>";
+
+/// Result of validating one value read from a source file.
+#[derive(Debug)]
+pub struct ValidationOutcome {
+    pub source: String,
+    /// Line of the value within the source, for sources holding one value per line.
+    pub line: Option<usize>,
+    pub error: Option<Error>,
+}
+
+impl ValidationOutcome {
+    pub fn new<T>(
+        source: impl Into<String>,
+        line: Option<usize>,
+        result: Result<T, Error>,
+    ) -> ValidationOutcome {
+        ValidationOutcome {
+            source: source.into(),
+            line,
+            error: result.err(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Source and, if any, line of the value, e.g. `values.ndjson:3`.
+    pub fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.source, line),
+            None => self.source.clone(),
+        }
+    }
+
+    /// Line and column of the error's span within the source, the value's line
+    /// offsetting the span's for sources holding one value per line.
+    pub fn error_position(&self) -> Option<(usize, usize)> {
+        let span = self.error.as_ref()?.span?;
+
+        Some((
+            self.line.unwrap_or(1) + span.start.line - 1,
+            span.start.column,
+        ))
+    }
+
+    /// Location of the error's span, e.g. `values.json:4:9`, or of the value if the
+    /// error has no span.
+    pub fn error_location(&self) -> String {
+        match self.error_position() {
+            Some((line, column)) => format!("{}:{}:{}", self.source, line, column),
+            None => self.location(),
+        }
+    }
+}
+
+/// JUnit XML document with one test case per outcome, failing ones carrying the
+/// error's kind, message and attribute.
+pub fn to_junit_xml(suite_name: &str, outcomes: &[ValidationOutcome]) -> String {
+    let failures = outcomes
+        .iter()
+        .filter(|outcome| !outcome.is_valid())
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\">\n",
+        outcomes.len(),
+        failures
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        escape_xml(suite_name),
+        outcomes.len(),
+        failures
+    ));
+
+    for outcome in outcomes {
+        let test_case = format!(
+            "    <testcase name=\"{}\" classname=\"{}\"",
+            escape_xml(&outcome.location()),
+            escape_xml(suite_name)
+        );
+
+        match &outcome.error {
+            None => xml.push_str(&format!("{} />\n", test_case)),
+            Some(error) => {
+                let mut details = String::new();
+
+                if let Some(attribute_id) = &error.attribute_id {
+                    details.push_str(&format!("attribute: {}", attribute_id));
+                }
+
+                xml.push_str(&format!(
+                    "{}>\n      <failure type=\"{:?}\" message=\"{}\">{}</failure>\n    </testcase>\n",
+                    test_case,
+                    error.kind(),
+                    escape_xml(&error.message),
+                    escape_xml(&details)
+                ));
+            }
+        }
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+
+    xml
+}
+
+/// SARIF log with one result per failing outcome, the error's kind being the
+/// result's rule and its attribute a logical location. Sources are located by
+/// their URI relative to the current directory, values read from stdin having no
+/// physical location.
+pub fn to_sarif(tool_name: &str, outcomes: &[ValidationOutcome]) -> Value {
+    let mut rules: Vec<String> = Vec::new();
+    let mut results: Vec<Value> = Vec::new();
+
+    for outcome in outcomes {
+        let error = match &outcome.error {
+            Some(error) => error,
+            None => continue,
+        };
+
+        let rule_id = format!("{:?}", error.kind());
+
+        if !rules.contains(&rule_id) {
+            rules.push(rule_id.clone());
+        }
+
+        let mut location: Map<String, Value> = Map::new();
+
+        if let Some(uri) = artifact_uri(&outcome.source) {
+            let mut physical_location = json!({ "artifactLocation": { "uri": uri } });
+
+            if let (Some((line, column)), Some(span)) = (outcome.error_position(), error.span) {
+                physical_location["region"] = json!({
+                    "startLine": line,
+                    "startColumn": column,
+                    "endLine": line + span.end.line - span.start.line,
+                    "endColumn": span.end.column,
+                });
+            } else if let Some(line) = outcome.line {
+                physical_location["region"] = json!({ "startLine": line });
+            }
+
+            location.insert("physicalLocation".to_string(), physical_location);
+        }
+
+        if let Some(attribute_id) = &error.attribute_id {
+            location.insert(
+                "logicalLocations".to_string(),
+                json!([{ "name": attribute_id, "kind": "member" }]),
+            );
+        }
+
+        let mut result = json!({
+            "ruleId": rule_id,
+            "level": "error",
+            "message": { "text": error.message },
+        });
+
+        if !location.is_empty() {
+            result["locations"] = json!([location]);
+        }
+
+        results.push(result);
+    }
+
+    let rules: Vec<Value> = rules
+        .into_iter()
+        .map(|rule_id| json!({ "id": rule_id }))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": { "driver": { "name": tool_name, "rules": rules } },
+            "results": results,
+        }],
+    })
+}
+
+/// URI of the source file with forward slashes and percent-encoded, relative when
+/// the file is within the current directory, none for stdin.
+fn artifact_uri(source: &str) -> Option<String> {
+    if source == STDIN_SOURCE {
+        return None;
+    }
+
+    let mut path = source.replace('\\', "/");
+
+    if let Ok(current_directory) = env::current_dir() {
+        let current_directory = current_directory.to_string_lossy().replace('\\', "/");
+        let prefix = format!("{}/", current_directory.trim_end_matches('/'));
+
+        if let Some(relative_path) = path.strip_prefix(&prefix) {
+            path = relative_path.to_string();
+        }
+    }
+
+    while let Some(relative_path) = path.strip_prefix("./") {
+        path = relative_path.to_string();
+    }
+
+    let bytes = path.as_bytes();
+    let has_drive = bytes.len() > 1 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+
+    if path.starts_with('/') {
+        Some(format!("file://{}", percent_encode(&path, false)))
+    } else if has_drive {
+        Some(format!(
+            "file:///{}:{}",
+            &path[..1],
+            percent_encode(&path[2..], false)
+        ))
+    } else {
+        Some(percent_encode(&path, true))
+    }
+}
+
+/// Percent-encodes every byte of the path but unreserved characters and slashes,
+/// colons being encoded too within relative paths, where they would read as a scheme.
+fn percent_encode(path: &str, encode_colon: bool) -> String {
+    let mut encoded_path = String::new();
+
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric()
+            || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/')
+            || (byte == b':' && !encode_colon)
+        {
+            encoded_path.push(byte as char);
+        } else {
+            encoded_path.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded_path
+}
+
+/// Error as `{ "kind", "message", "attribute_id", "line", "column" }`, the kind
+/// being the name of the `ErrorKind` and the position the start of its span.
+pub fn error_to_json(error: &Error) -> Value {
+    json!({
+        "kind": format!("{:?}", error.kind()),
+        "message": error.message,
+        "attribute_id": error.attribute_id,
+        "line": error.span.map(|span| span.start.line),
+        "column": error.span.map(|span| span.start.column),
+    })
+}
+
+/// Validated value as `{ "valid": true, "definition", "definition_type",
+/// "category_chain", "value" }`.
+pub fn definition_value_to_json(definition_value: &DefinitionValue) -> Value {
+    json!({
+        "valid": true,
+        "definition": definition_value.definition(),
+        "definition_type": definition_value.definition_type(),
+        "category_chain": definition_value.category_chain().iter().collect::<Vec<&String>>(),
+        "value": definition_value.value(),
+    })
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
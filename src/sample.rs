@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+use serde_json::{Map, Number, Value};
+
+use crate::category_attributes::collect_from_category_chain;
+use crate::category_chain::CategoryIndex;
+use crate::definition_type::{DefinitionType, DefinitionTypeMarkers};
+use crate::error::{Error, ErrorKind};
+use crate::schema_validator::{VALUE_TYPE, VALUE_VERSION};
+
+const STRING_CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+const MAX_STRING_LENGTH: u64 = 12;
+const MAX_INTEGER: i64 = 1_000_000;
+
+type SampleValueGenerator = Box<dyn Fn(&mut SampleRng) -> Value + Send + Sync>;
+
+/// Small, seedable, pseudo random number generator (SplitMix64) producing the same
+/// samples for the same seed on every platform. Not suitable for cryptography.
+#[derive(Debug, Clone)]
+pub struct SampleRng {
+    state: u64,
+}
+
+impl SampleRng {
+    pub fn seed_from_u64(seed: u64) -> SampleRng {
+        SampleRng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+
+    /// Number within `0..bound`, `bound` being greater than zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Number within `min..=max`.
+    pub fn range_i64(&mut self, min: i64, max: i64) -> i64 {
+        let span = max.abs_diff(min).saturating_add(1);
+
+        min.wrapping_add(self.below(span) as i64)
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
+/// Generates random values of a definition's selectable categories which pass
+/// `SchemaValidator`'s validation.
+///
+/// Attributes carry no constraints besides their data type, so every generator
+/// produces any value of its data type. Optional attributes are randomly omitted.
+pub struct SampleGenerator {
+    generators: HashMap<String, SampleValueGenerator>,
+    definition_type_markers: DefinitionTypeMarkers,
+}
+
+impl SampleGenerator {
+    fn initialize_base_generators(generators: &mut HashMap<String, SampleValueGenerator>) {
+        generators.insert("string".to_string(), Box::new(generate_string));
+        generators.insert("integer".to_string(), Box::new(generate_integer));
+        generators.insert("decimal".to_string(), Box::new(generate_decimal));
+        generators.insert("boolean".to_string(), Box::new(generate_boolean));
+    }
+
+    /// Registers the generator of attributes of a custom data type, whose values must
+    /// pass the validation registered for the data type.
+    pub fn register_generator(&mut self, data_type: String, generator: SampleValueGenerator) {
+        self.generators.insert(data_type, generator);
+    }
+
+    /// Markers set by the generated values, which must match the validator's markers.
+    pub fn set_definition_type_markers(&mut self, definition_type_markers: DefinitionTypeMarkers) {
+        self.definition_type_markers = definition_type_markers;
+    }
+
+    /// Generates a value of the category, which must be selectable as last and hold
+    /// a definition type marker attribute.
+    pub fn generate(
+        &self,
+        definition: &Definition,
+        category_id: &String,
+        rng: &mut SampleRng,
+    ) -> Result<Map<String, Value>, Error> {
+        let category_index = CategoryIndex::new(definition);
+
+        match category_index.get(category_id) {
+            Some(category) if !category.selectable_as_last => {
+                return Err(Error::new(
+                    ErrorKind::InvalidArgument,
+                    format!("category id '{}' is not selectable as last", category_id),
+                ))
+            }
+            _ => (),
+        }
+
+        let category_chain = category_index.build_chain(category_id)?;
+        let attributes = collect_from_category_chain(&category_index, &category_chain)?;
+        let definition_type = match self.definition_type(&attributes)? {
+            Some(definition_type) => definition_type,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidDefinition,
+                    format!(
+                        "category id '{}' has no definition type marker attribute, its values cannot pass validation",
+                        category_id
+                    ),
+                ))
+            }
+        };
+
+        let mut value: Map<String, Value> = Map::new();
+        value.insert(
+            VALUE_VERSION.to_string(),
+            Value::String(definition.version()),
+        );
+        value.insert(VALUE_TYPE.to_string(), Value::String(category_id.clone()));
+
+        for attribute in attributes.as_slice() {
+            if let Some(marker_definition_type) = self.marker_definition_type(&attribute.id) {
+                if marker_definition_type == &definition_type {
+                    value.insert(attribute.id.clone(), Value::Bool(true));
+                }
+
+                continue;
+            }
+
+            let generator = match self.generators.get(&attribute.data_type) {
+                Some(generator) => generator,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::DataTypeNotRegistered,
+                        format!(
+                            "no sample generator found for data type '{}'",
+                            attribute.data_type
+                        ),
+                    )
+                    .with_attribute_id(attribute.id.clone()))
+                }
+            };
+
+            if attribute.optional && rng.next_bool() {
+                continue;
+            }
+
+            value.insert(attribute.id.clone(), generator(rng));
+        }
+
+        Ok(value)
+    }
+
+    /// Definition type marked by the required markers or, without any, by the first
+    /// optional one.
+    fn definition_type(
+        &self,
+        attributes: &[ValidatedSourceAttribute],
+    ) -> Result<Option<DefinitionType>, Error> {
+        let mut required_definition_type: Option<&DefinitionType> = None;
+        let mut optional_definition_type: Option<&DefinitionType> = None;
+
+        for attribute in attributes {
+            let marker_definition_type = match self.marker_definition_type(&attribute.id) {
+                Some(marker_definition_type) => marker_definition_type,
+                None => continue,
+            };
+
+            if attribute.optional {
+                optional_definition_type.get_or_insert(marker_definition_type);
+                continue;
+            }
+
+            match required_definition_type {
+                Some(definition_type) if definition_type != marker_definition_type => {
+                    return Err(Error::new(
+                        ErrorKind::DefinitionTypeConflict,
+                        format!(
+                            "required definition type markers of '{:?}' and '{:?}' conflict",
+                            definition_type, marker_definition_type
+                        ),
+                    )
+                    .with_attribute_id(attribute.id.clone()))
+                }
+                Some(_) => (),
+                None => required_definition_type = Some(marker_definition_type),
+            }
+        }
+
+        Ok(required_definition_type
+            .or(optional_definition_type)
+            .cloned())
+    }
+
+    fn marker_definition_type(&self, attribute_id: &str) -> Option<&DefinitionType> {
+        self.definition_type_markers
+            .markers()
+            .find(|(marker_attribute_id, _)| marker_attribute_id.as_str() == attribute_id)
+            .map(|(_, definition_type)| definition_type)
+    }
+}
+
+impl Default for SampleGenerator {
+    fn default() -> Self {
+        let mut generators: HashMap<String, SampleValueGenerator> = HashMap::new();
+
+        SampleGenerator::initialize_base_generators(&mut generators);
+
+        SampleGenerator {
+            generators,
+            definition_type_markers: DefinitionTypeMarkers::default(),
+        }
+    }
+}
+
+pub fn generate_string(rng: &mut SampleRng) -> Value {
+    let length = 1 + rng.below(MAX_STRING_LENGTH);
+
+    let string: String = (0..length)
+        .map(|_| STRING_CHARACTERS[rng.below(STRING_CHARACTERS.len() as u64) as usize] as char)
+        .collect();
+
+    Value::String(string)
+}
+
+pub fn generate_integer(rng: &mut SampleRng) -> Value {
+    Value::Number(Number::from(rng.range_i64(-MAX_INTEGER, MAX_INTEGER)))
+}
+
+pub fn generate_decimal(rng: &mut SampleRng) -> Value {
+    let cents = rng.range_i64(-MAX_INTEGER * 100, MAX_INTEGER * 100);
+
+    match Number::from_f64(cents as f64 / 100.0) {
+        Some(decimal) => Value::Number(decimal),
+        None => Value::Number(Number::from(0)),
+    }
+}
+
+pub fn generate_boolean(rng: &mut SampleRng) -> Value {
+    Value::Bool(rng.next_bool())
+}
+
+/// `proptest` strategy of values of the category, validating the category up front.
+#[cfg(feature = "proptest")]
+pub fn strategy(
+    sample_generator: std::sync::Arc<SampleGenerator>,
+    definition: Definition,
+    category_id: String,
+) -> Result<impl proptest::strategy::Strategy<Value = Map<String, Value>>, Error> {
+    use proptest::prelude::{any, Strategy};
+
+    sample_generator.generate(&definition, &category_id, &mut SampleRng::seed_from_u64(0))?;
+
+    Ok(any::<u64>().prop_map(move |seed| {
+        sample_generator
+            .generate(
+                &definition,
+                &category_id,
+                &mut SampleRng::seed_from_u64(seed),
+            )
+            .expect("sample generation failed after succeeding once")
+    }))
+}
@@ -2,25 +2,35 @@ use std::collections::HashMap;
 
 use cooplan_definitions_lib::{
     definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
-    validated_source_category::ValidatedSourceCategory,
 };
 use serde_json::{Map, Value};
 
-use crate::category_chain::{build_from_definition_and_category, CategoryChain};
+use crate::category_attributes::collect_from_category_chain;
+use crate::category_chain::CategoryIndex;
 use crate::{
+    definition_type::{DefinitionType, DefinitionTypeDetection, DefinitionTypeMarkerSource},
     definition_value::DefinitionValue,
     error::{Error, ErrorKind},
+    lint::{lint_definition, LintReport},
+    source_map::{locate_member, SourcePosition, SourceSpan},
     validations::{validate_boolean, validate_decimal, validate_integer, validate_string},
+    version_policy::VersionPolicy,
 };
 
-const VALUE_VERSION: &str = "version";
-const VALUE_TYPE: &str = "type";
+pub(crate) const VALUE_VERSION: &str = "version";
+pub(crate) const VALUE_TYPE: &str = "type";
 
 type Validation = Box<dyn Fn(&Value) -> Result<(), Error> + Send>;
 
+/// Validates values against the categories of a definition.
+///
+/// A value must hold every required attribute of its category chain, whereas its
+/// optional attributes may be left out. Attributes present in the value are
+/// validated by the validation of their data type, optional or not.
 pub struct SchemaValidator {
-    categories: HashMap<String, ValidatedSourceCategory>,
     validations: HashMap<String, Validation>,
+    version_policy: VersionPolicy,
+    definition_type_detection: DefinitionTypeDetection,
 }
 
 impl SchemaValidator {
@@ -35,133 +45,133 @@ impl SchemaValidator {
         self.validations.insert(data_type, validation);
     }
 
+    pub fn set_version_policy(&mut self, version_policy: VersionPolicy) {
+        self.version_policy = version_policy;
+    }
+
+    pub fn version_policy(&self) -> &VersionPolicy {
+        &self.version_policy
+    }
+
+    pub fn set_definition_type_marker_source(
+        &mut self,
+        definition_type_marker_source: DefinitionTypeMarkerSource,
+    ) {
+        self.definition_type_detection
+            .set_marker_source(definition_type_marker_source);
+    }
+
+    /// Infers the definition type of values under the root category from their
+    /// category chain, rejecting values whose markers disagree.
+    pub fn register_definition_type_root(
+        &mut self,
+        category_id: String,
+        definition_type: DefinitionType,
+    ) {
+        self.definition_type_detection
+            .register_root(category_id, definition_type);
+    }
+
+    /// Checks the definition against the registered validations, reporting every
+    /// problem values of the definition would run into.
+    pub fn lint(&self, definition: &Definition) -> LintReport {
+        lint_definition(definition, |data_type| {
+            self.validations.contains_key(data_type)
+        })
+    }
+
+    /// Validates the value's source, errors carrying the span of the offending
+    /// token.
     pub fn validate(
         &mut self,
         value: String,
         definition: Definition,
     ) -> Result<DefinitionValue, Error> {
         match serde_json::from_str(value.as_str()) {
-            Ok::<Map<String, Value>, _>(mut object) => {
-                let value_definition_version = match self.try_get_version(&object) {
-                    Ok(version) => version,
-                    Err(error) => return Err(error),
-                };
-
-                if definition.version().to_lowercase() != value_definition_version.to_lowercase() {
-                    return Err(Error::new(
-                        ErrorKind::ValueDefinitionMismatch,
-                        format!(
-                            "value's version '{}' != definition's version '{}'",
-                            value_definition_version,
-                            definition.version()
-                        ),
-                    ));
-                }
-
-                let value_type = match self.try_get_type(&object) {
-                    Ok(value_type) => value_type,
-                    Err(error) => return Err(error),
-                };
-
-                let attributes: Vec<ValidatedSourceAttribute> =
-                    match self.try_get_attributes_from_definition(&definition, &value_type) {
-                        Ok(attributes) => attributes,
-                        Err(error) => return Err(error),
-                    };
-
-                let mut scoped_value: Map<String, Value> = Map::new();
-
-                for attribute in attributes.as_slice() {
-                    let attribute_value = match object.remove(&attribute.id) {
-                        Some(attribute_value) => attribute_value,
-                        None => {
-                            return Err(Error::new(
-                                ErrorKind::InvalidValue,
-                                format!(
-                                    "failed to find attribute id '{}' within value",
-                                    attribute.id
-                                ),
-                            ))
-                        }
-                    };
-
-                    match self.validations.get(&attribute.data_type) {
-                        Some(validation) => match validation(&attribute_value) {
-                            Ok(_) => {
-                                scoped_value.insert(attribute.id.clone(), attribute_value);
-                            }
-                            Err(error) => return Err(error),
-                        },
-                        None => {
-                            return Err(Error::new(
-                                ErrorKind::ValidationNotRegistered,
-                                format!(
-                                    "no validation found for data type '{}'",
-                                    attribute.data_type
-                                ),
-                            ))
-                        }
-                    }
-                }
-
-                let category_chain = build_from_definition_and_category(&definition, &value_type);
-
-                let definition_value =
-                    DefinitionValue::try_new(&definition, category_chain, scoped_value)?;
-
-                Ok(definition_value)
+            Ok::<Map<String, Value>, _>(object) => {
+                self.validate_located(object, definition, Some(value.as_str()))
             }
             Err(error) => Err(Error::new(
                 ErrorKind::DeserializationFailure,
                 format!("failed to deserialize value: {}", error),
-            )),
+            )
+            .with_span(SourceSpan::at(SourcePosition::of_json_error(
+                value.as_str(),
+                &error,
+            )))),
         }
     }
 
     pub fn validate_object(
         &mut self,
-        mut object: Map<String, Value>,
+        object: Map<String, Value>,
         definition: Definition,
     ) -> Result<DefinitionValue, Error> {
-        let value_definition_version = match self.try_get_version(&object) {
-            Ok(version) => version,
-            Err(error) => return Err(error),
-        };
+        self.validate_located(object, definition, None)
+    }
 
-        if definition.version().to_lowercase() != value_definition_version.to_lowercase() {
-            return Err(Error::new(
-                ErrorKind::ValueDefinitionMismatch,
-                format!(
-                    "value's version '{}' != definition's version '{}'",
-                    value_definition_version,
-                    definition.version()
+    /// Validates the object, locating errors within the source it was deserialized
+    /// from, which is only mapped once validation fails.
+    pub(crate) fn validate_located(
+        &mut self,
+        mut object: Map<String, Value>,
+        definition: Definition,
+        source: Option<&str>,
+    ) -> Result<DefinitionValue, Error> {
+        let value_definition_version = self
+            .try_get_version(&object)
+            .map_err(|error| locate_member(error, source, VALUE_VERSION))?;
+
+        if !self
+            .version_policy
+            .is_compatible(&definition.version(), &value_definition_version)
+        {
+            return Err(locate_member(
+                Error::new(
+                    ErrorKind::ValueDefinitionMismatch,
+                    format!(
+                        "value's version '{}' is not compatible with definition's version '{}' ({:?})",
+                        value_definition_version,
+                        definition.version(),
+                        self.version_policy.kind()
+                    ),
                 ),
+                source,
+                VALUE_VERSION,
             ));
         }
 
-        let value_type = match self.try_get_type(&object) {
-            Ok(value_type) => value_type,
-            Err(error) => return Err(error),
-        };
+        let value_type = self
+            .try_get_type(&object)
+            .map_err(|error| locate_member(error, source, VALUE_TYPE))?;
+
+        let category_index = CategoryIndex::new(&definition);
+        let category_chain = category_index
+            .build_chain(&value_type)
+            .map_err(|error| locate_member(error, source, VALUE_TYPE))?;
 
         let attributes: Vec<ValidatedSourceAttribute> =
-            match self.try_get_attributes_from_definition(&definition, &value_type) {
-                Ok(attributes) => attributes,
-                Err(error) => return Err(error),
-            };
+            collect_from_category_chain(&category_index, &category_chain)
+                .map_err(|error| locate_member(error, source, VALUE_TYPE))?;
 
         let mut scoped_value: Map<String, Value> = Map::new();
 
         for attribute in attributes.as_slice() {
             let attribute_value = match object.remove(&attribute.id) {
                 Some(attribute_value) => attribute_value,
+                None if attribute.optional => continue,
                 None => {
-                    return Err(Error::new(
-                        ErrorKind::InvalidValue,
-                        format!(
-                            "failed to find attribute id '{}' within value",
-                            attribute.id
-                        ),
+                    return Err(locate_member(
+                        Error::new(
+                            ErrorKind::InvalidValue,
+                            format!(
+                                "failed to find attribute id '{}' within value",
+                                attribute.id
+                            ),
+                        )
+                        .with_attribute_id(attribute.id.clone()),
+                        source,
+                        &attribute.id,
                     ))
                 }
             };
@@ -171,28 +181,45 @@ impl SchemaValidator {
                     Ok(_) => {
                         scoped_value.insert(attribute.id.clone(), attribute_value);
                     }
-                    Err(error) => return Err(error),
+                    Err(error) => {
+                        return Err(locate_member(
+                            error.with_attribute_id(attribute.id.clone()),
+                            source,
+                            &attribute.id,
+                        ))
+                    }
                 },
                 None => {
-                    return Err(Error::new(
-                        ErrorKind::ValidationNotRegistered,
-                        format!(
-                            "no validation found for data type '{}'",
-                            attribute.data_type
-                        ),
+                    return Err(locate_member(
+                        Error::new(
+                            ErrorKind::ValidationNotRegistered,
+                            format!(
+                                "no validation found for data type '{}'",
+                                attribute.data_type
+                            ),
+                        )
+                        .with_attribute_id(attribute.id.clone()),
+                        source,
+                        &attribute.id,
                     ))
                 }
             }
         }
 
-        let category_chain = build_from_definition_and_category(&definition, &value_type);
-
-        let definition_value = DefinitionValue::try_new(&definition, category_chain, scoped_value)?;
+        let definition_value = DefinitionValue::try_new(
+            &definition,
+            category_chain,
+            scoped_value,
+            attributes,
+            self.version_policy.kind(),
+            &self.definition_type_detection,
+        )
+        .map_err(|error| locate_member(error, source, VALUE_TYPE))?;
 
         Ok(definition_value)
     }
 
-    fn try_get_version(&self, object: &Map<String, Value>) -> Result<String, Error> {
+    pub(crate) fn try_get_version(&self, object: &Map<String, Value>) -> Result<String, Error> {
         match object.get(VALUE_VERSION) {
             Some(version) => match version.as_str() {
                 Some(version_string) => Ok(version_string.to_string()),
@@ -223,52 +250,6 @@ impl SchemaValidator {
             )),
         }
     }
-
-    fn try_get_attributes_from_definition(
-        &mut self,
-        definition: &Definition,
-        category_id: &String,
-    ) -> Result<Vec<ValidatedSourceAttribute>, Error> {
-        self.categories.clear();
-
-        for category in definition.categories() {
-            self.categories.insert(category.id.clone(), category);
-        }
-
-        let mut attributes: Vec<ValidatedSourceAttribute> = Vec::new();
-
-        match self.add_attributes_from_category(&mut attributes, category_id) {
-            Ok(_) => Ok(attributes),
-            Err(error) => Err(error),
-        }
-    }
-
-    fn add_attributes_from_category(
-        &self,
-        attributes: &mut Vec<ValidatedSourceAttribute>,
-        category_id: &String,
-    ) -> Result<(), Error> {
-        let category = match self.categories.get(category_id) {
-            Some(category) => category,
-            None => {
-                return Err(Error::new(
-                    ErrorKind::InvalidValue,
-                    format!("cannot found category id '{}'", category_id),
-                ))
-            }
-        };
-
-        for attribute in category.attributes.as_slice() {
-            attributes.push(attribute.clone());
-        }
-
-        match &category.parent {
-            Some(parent_category_id) => {
-                self.add_attributes_from_category(attributes, &parent_category_id)
-            }
-            None => Ok(()),
-        }
-    }
 }
 
 impl Default for SchemaValidator {
@@ -278,8 +259,9 @@ impl Default for SchemaValidator {
         SchemaValidator::initialize_base_validations(&mut validations);
 
         SchemaValidator {
-            categories: HashMap::new(),
             validations,
+            version_policy: VersionPolicy::default(),
+            definition_type_detection: DefinitionTypeDetection::default(),
         }
     }
 }
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+
+use crate::error::{Error, ErrorKind};
+
+/// Position within a JSON source, its line and column starting at one and the
+/// column counting characters.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SourcePosition {
+    /// Byte offset within the source.
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourcePosition {
+    /// Position of the byte offset within the source, clamped to the source's end.
+    pub fn at(source: &str, offset: usize) -> SourcePosition {
+        let mut offset = offset.min(source.len());
+
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let prefix = &source[..offset];
+        let line_start = prefix.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+        SourcePosition {
+            offset,
+            line: prefix.matches('\n').count() + 1,
+            column: prefix[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Position at which deserializing the source failed.
+    pub fn of_json_error(source: &str, error: &serde_json::Error) -> SourcePosition {
+        let line_start: usize = source
+            .split_inclusive('\n')
+            .take(error.line().saturating_sub(1))
+            .map(str::len)
+            .sum();
+
+        SourcePosition::at(source, line_start + error.column().saturating_sub(1))
+    }
+}
+
+/// Token of a JSON source, from its first character up to the one following it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SourceSpan {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl SourceSpan {
+    /// Empty span at the position.
+    pub fn at(position: SourcePosition) -> SourceSpan {
+        SourceSpan {
+            start: position,
+            end: position,
+        }
+    }
+}
+
+/// Spans of every key and value of a JSON source, addressed by JSON pointer, the
+/// root value being addressed by the empty pointer.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    keys: HashMap<String, SourceSpan>,
+    values: HashMap<String, SourceSpan>,
+}
+
+impl SourceMap {
+    pub fn parse(source: &str) -> Result<SourceMap, Error> {
+        let mut scanner = Scanner {
+            source,
+            position: SourcePosition {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            source_map: SourceMap {
+                keys: HashMap::new(),
+                values: HashMap::new(),
+            },
+        };
+
+        scanner.scan_value(String::new())?;
+        scanner.skip_whitespace();
+
+        if scanner.peek().is_some() {
+            return Err(scanner.error("unexpected trailing characters"));
+        }
+
+        Ok(scanner.source_map)
+    }
+
+    pub fn root(&self) -> Option<SourceSpan> {
+        self.value("")
+    }
+
+    pub fn key(&self, pointer: &str) -> Option<SourceSpan> {
+        self.keys.get(pointer).copied()
+    }
+
+    pub fn value(&self, pointer: &str) -> Option<SourceSpan> {
+        self.values.get(pointer).copied()
+    }
+
+    /// Span of the root object's member value, falling back to the root value when
+    /// the member is missing.
+    pub fn member_or_root(&self, key: &str) -> Option<SourceSpan> {
+        self.value(&member_pointer("", key)).or_else(|| self.root())
+    }
+}
+
+/// Attaches the span of the root object's member, or of the root object when the
+/// member is missing, to the error of a value validated from its source. The source
+/// is only mapped here, valid values never paying for it.
+pub(crate) fn locate_member(error: Error, source: Option<&str>, key: &str) -> Error {
+    let source_map = source.and_then(|source| SourceMap::parse(source).ok());
+
+    match source_map.and_then(|source_map| source_map.member_or_root(key)) {
+        Some(span) => error.with_span(span),
+        None => error,
+    }
+}
+
+/// JSON pointer of the member of the value at the parent pointer.
+pub fn member_pointer(parent_pointer: &str, key: &str) -> String {
+    format!(
+        "{}/{}",
+        parent_pointer,
+        key.replace('~', "~0").replace('/', "~1")
+    )
+}
+
+struct Scanner<'a> {
+    source: &'a str,
+    position: SourcePosition,
+    source_map: SourceMap,
+}
+
+impl<'a> Scanner<'a> {
+    fn scan_value(&mut self, pointer: String) -> Result<(), Error> {
+        self.skip_whitespace();
+
+        let start = self.position;
+
+        match self.peek() {
+            Some(b'{') => self.scan_object(&pointer)?,
+            Some(b'[') => self.scan_array(&pointer)?,
+            Some(b'"') => self.scan_string()?,
+            Some(b'-' | b'0'..=b'9') => self.advance_while(|byte| {
+                matches!(byte, b'+' | b'-' | b'.' | b'0'..=b'9' | b'e' | b'E')
+            }),
+            Some(b't' | b'f' | b'n') => self.advance_while(|byte| byte.is_ascii_alphabetic()),
+            _ => return Err(self.error("expected a value")),
+        }
+
+        let span = SourceSpan {
+            start,
+            end: self.position,
+        };
+        self.source_map.values.insert(pointer, span);
+
+        Ok(())
+    }
+
+    fn scan_object(&mut self, pointer: &str) -> Result<(), Error> {
+        self.advance();
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.advance();
+
+            return Ok(());
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected an object key"));
+            }
+
+            let start = self.position;
+            self.scan_string()?;
+            let span = SourceSpan {
+                start,
+                end: self.position,
+            };
+
+            let key: String =
+                match serde_json::from_str(&self.source[start.offset..span.end.offset]) {
+                    Ok(key) => key,
+                    Err(error) => return Err(self.error(&format!("invalid object key: {}", error))),
+                };
+            let member_pointer = member_pointer(pointer, &key);
+            self.source_map.keys.insert(member_pointer.clone(), span);
+
+            self.skip_whitespace();
+
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':'"));
+            }
+
+            self.advance();
+            self.scan_value(member_pointer)?;
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.advance(),
+                Some(b'}') => {
+                    self.advance();
+
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn scan_array(&mut self, pointer: &str) -> Result<(), Error> {
+        self.advance();
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.advance();
+
+            return Ok(());
+        }
+
+        let mut index = 0;
+
+        loop {
+            self.scan_value(format!("{}/{}", pointer, index))?;
+            self.skip_whitespace();
+            index += 1;
+
+            match self.peek() {
+                Some(b',') => self.advance(),
+                Some(b']') => {
+                    self.advance();
+
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn scan_string(&mut self) -> Result<(), Error> {
+        self.advance();
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.advance();
+
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.advance();
+                    self.advance();
+                }
+                Some(_) => self.advance(),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.advance_while(|byte| matches!(byte, b' ' | b'\t' | b'\n' | b'\r'));
+    }
+
+    fn advance_while(&mut self, predicate: impl Fn(u8) -> bool) {
+        while let Some(byte) = self.peek() {
+            if !predicate(byte) {
+                break;
+            }
+
+            self.advance();
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position.offset).copied()
+    }
+
+    /// Moves past the current byte, counting a column at the start of every character.
+    fn advance(&mut self) {
+        let byte = match self.peek() {
+            Some(byte) => byte,
+            None => return,
+        };
+
+        self.position.offset += 1;
+
+        if byte == b'\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else if self.source.is_char_boundary(self.position.offset) {
+            self.position.column += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::new(
+            ErrorKind::DeserializationFailure,
+            format!(
+                "failed to map source: {} at line {} column {}",
+                message, self.position.line, self.position.column
+            ),
+        )
+        .with_span(SourceSpan::at(self.position))
+    }
+}
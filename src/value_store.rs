@@ -0,0 +1,126 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::definition_type::DefinitionType;
+use crate::definition_value::DefinitionValue;
+
+/// In memory store of validated values, indexed by every category of their category
+/// chain and by their definition type.
+///
+/// Since a value is indexed by all of its ancestors, the values of a category's
+/// subtree are found without scanning the store.
+#[derive(Debug)]
+pub struct ValueStore<K> {
+    values: HashMap<K, DefinitionValue>,
+    categories: HashMap<String, HashSet<K>>,
+    definition_types: HashMap<DefinitionType, HashSet<K>>,
+}
+
+impl<K: Eq + Hash + Clone> ValueStore<K> {
+    pub fn new() -> ValueStore<K> {
+        ValueStore {
+            values: HashMap::new(),
+            categories: HashMap::new(),
+            definition_types: HashMap::new(),
+        }
+    }
+
+    /// Stores the value under the key, replacing and returning the value previously
+    /// stored under the same key.
+    pub fn insert(&mut self, key: K, definition_value: DefinitionValue) -> Option<DefinitionValue> {
+        let replaced_value = self.remove(&key);
+
+        for category in definition_value.category_chain().iter() {
+            self.categories
+                .entry(category.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+
+        self.definition_types
+            .entry(definition_value.definition_type().clone())
+            .or_default()
+            .insert(key.clone());
+
+        self.values.insert(key, definition_value);
+
+        replaced_value
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<DefinitionValue> {
+        let definition_value = self.values.remove(key)?;
+
+        for category in definition_value.category_chain().iter() {
+            if let Some(keys) = self.categories.get_mut(category) {
+                keys.remove(key);
+
+                if keys.is_empty() {
+                    self.categories.remove(category);
+                }
+            }
+        }
+
+        if let Some(keys) = self
+            .definition_types
+            .get_mut(definition_value.definition_type())
+        {
+            keys.remove(key);
+
+            if keys.is_empty() {
+                self.definition_types
+                    .remove(definition_value.definition_type());
+            }
+        }
+
+        Some(definition_value)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&DefinitionValue> {
+        self.values.get(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Stored values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &DefinitionValue)> {
+        self.values.iter()
+    }
+
+    /// Values of the category and of all its descendant categories, in no particular order.
+    pub fn in_category(&self, category: &str) -> impl Iterator<Item = (&K, &DefinitionValue)> {
+        self.lookup(self.categories.get(category))
+    }
+
+    /// Values of the definition type, in no particular order.
+    pub fn of_definition_type(
+        &self,
+        definition_type: &DefinitionType,
+    ) -> impl Iterator<Item = (&K, &DefinitionValue)> {
+        self.lookup(self.definition_types.get(definition_type))
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        keys: Option<&'a HashSet<K>>,
+    ) -> impl Iterator<Item = (&'a K, &'a DefinitionValue)> {
+        keys.into_iter()
+            .flatten()
+            .filter_map(|key| self.values.get_key_value(key))
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for ValueStore<K> {
+    fn default() -> Self {
+        ValueStore::new()
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Policy deciding whether a value's version can be validated by a definition's version.
+#[derive(Debug, Clone, Default)]
+pub enum VersionPolicy {
+    /// Versions must be equal, ignoring case.
+    #[default]
+    Exact,
+    /// Versions must share the major version, the value's minor version being lower
+    /// or equal than the definition's. Non semantic versions must be equal.
+    SemverCompatible,
+    /// Besides equal versions, a definition version accepts the value versions it is
+    /// mapped to. Versions are compared ignoring case.
+    CompatibilityTable(HashMap<String, Vec<String>>),
+}
+
+/// Policy applied when validating a value, without its configuration.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Default)]
+pub enum VersionPolicyKind {
+    #[default]
+    Exact,
+    SemverCompatible,
+    CompatibilityTable,
+}
+
+impl VersionPolicy {
+    pub fn kind(&self) -> VersionPolicyKind {
+        match self {
+            VersionPolicy::Exact => VersionPolicyKind::Exact,
+            VersionPolicy::SemverCompatible => VersionPolicyKind::SemverCompatible,
+            VersionPolicy::CompatibilityTable(_) => VersionPolicyKind::CompatibilityTable,
+        }
+    }
+
+    pub fn is_compatible(&self, definition_version: &str, value_version: &str) -> bool {
+        if definition_version.to_lowercase() == value_version.to_lowercase() {
+            return true;
+        }
+
+        match self {
+            VersionPolicy::Exact => false,
+            VersionPolicy::SemverCompatible => {
+                match (
+                    parse_semantic_version(definition_version),
+                    parse_semantic_version(value_version),
+                ) {
+                    (
+                        Some((definition_major, definition_minor, _)),
+                        Some((value_major, value_minor, _)),
+                    ) => definition_major == value_major && value_minor <= definition_minor,
+                    _ => false,
+                }
+            }
+            VersionPolicy::CompatibilityTable(compatibility_table) => {
+                let definition_version = definition_version.to_lowercase();
+                let value_version = value_version.to_lowercase();
+
+                compatibility_table
+                    .iter()
+                    .filter(|(version, _)| version.to_lowercase() == definition_version)
+                    .flat_map(|(_, compatible_versions)| compatible_versions)
+                    .any(|compatible_version| compatible_version.to_lowercase() == value_version)
+            }
+        }
+    }
+}
+
+/// Parses `[v]major[.minor[.patch]]`, ignoring pre-release and build metadata.
+fn parse_semantic_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim();
+    let version = version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .unwrap_or(version);
+    let version = match version.find(['-', '+']) {
+        Some(index) => &version[..index],
+        None => version,
+    };
+
+    let mut components = version.split('.');
+    let major = components.next()?.parse::<u64>().ok()?;
+    let minor = match components.next() {
+        Some(minor) => minor.parse::<u64>().ok()?,
+        None => 0,
+    };
+    let patch = match components.next() {
+        Some(patch) => patch.parse::<u64>().ok()?,
+        None => 0,
+    };
+
+    if components.next().is_some() {
+        return None;
+    }
+
+    Some((major, minor, patch))
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+
+use cooplan_definition_schema_validator::{
+    category_attributes::collect_from_definition_and_category, error::ErrorKind,
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_attribute, build_product_definition};
+
+fn build_definition(fruit_attributes: Vec<ValidatedSourceAttribute>) -> Definition {
+    build_product_definition(
+        vec![
+            build_attribute("10", "string", true),
+            build_attribute("11", "integer", false),
+        ],
+        fruit_attributes,
+    )
+}
+
+#[test]
+fn child_may_make_parent_attribute_required() {
+    let definition = build_definition(vec![build_attribute("10", "string", false)]);
+
+    let attributes = collect_from_definition_and_category(&definition, &"2".to_string())
+        .expect("failed to collect attributes");
+    let name_attributes: Vec<&ValidatedSourceAttribute> = attributes
+        .iter()
+        .filter(|attribute| attribute.id == "10")
+        .collect();
+
+    assert_eq!(1, name_attributes.len());
+    assert!(!name_attributes[0].optional);
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        SchemaValidator::default()
+            .validate(
+                String::from(
+                    "{ \"type\": \"2\", \"version\": \"1\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+                ),
+                definition,
+            )
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn child_changing_parent_attribute_data_type_is_rejected() {
+    let error = SchemaValidator::default()
+        .validate(
+            String::from(
+                "{ \"type\": \"2\", \"version\": \"1\", \"10\": \"Pear\", \"11\": \"600\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+            ),
+            build_definition(vec![build_attribute("11", "string", false)]),
+        )
+        .unwrap_err();
+
+    assert_eq!(ErrorKind::InvalidDefinition, error.kind());
+    assert!(error.message.contains("data type"));
+}
+
+#[test]
+fn child_making_parent_attribute_optional_is_rejected() {
+    assert_eq!(
+        ErrorKind::InvalidDefinition,
+        collect_from_definition_and_category(
+            &build_definition(vec![build_attribute("11", "integer", true)]),
+            &"2".to_string()
+        )
+        .unwrap_err()
+        .kind()
+    );
+}
+
+#[test]
+fn attribute_declared_twice_by_category_is_rejected() {
+    assert_eq!(
+        ErrorKind::InvalidDefinition,
+        collect_from_definition_and_category(
+            &build_definition(vec![
+                build_attribute("12", "decimal", false),
+                build_attribute("12", "decimal", false),
+            ]),
+            &"2".to_string()
+        )
+        .unwrap_err()
+        .kind()
+    );
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    category_chain::{build_from_definition_and_category, CategoryChain, CategoryIndex},
+    error::ErrorKind,
+};
+
+use common::{build_category, build_named_category};
+
+fn build_definition() -> Definition {
+    Definition::new(
+        "1".to_string(),
+        vec![
+            build_named_category("3", "pear", Some("2"), true, vec![]),
+            build_named_category("1", "product", None, true, vec![]),
+            build_named_category("2", "fruit", Some("1"), true, vec![]),
+        ],
+    )
+}
+
+#[test]
+fn chain_is_ordered_from_root_to_leaf() {
+    let category_chain = build_from_definition_and_category(&build_definition(), &"3".to_string())
+        .expect("failed to build category chain");
+
+    assert_eq!(
+        vec!["1", "2", "3"],
+        category_chain.iter().collect::<Vec<&String>>()
+    );
+    assert_eq!(
+        vec!["product", "fruit", "pear"],
+        category_chain.names().collect::<Vec<&String>>()
+    );
+    assert_eq!(Some(&"1".to_string()), category_chain.root());
+    assert_eq!(Some(&"3".to_string()), category_chain.leaf());
+    assert_eq!(3, category_chain.depth());
+}
+
+#[test]
+fn ancestry_queries() {
+    let category_chain = build_from_definition_and_category(&build_definition(), &"3".to_string())
+        .expect("failed to build category chain");
+
+    assert!(category_chain.is_descendant_of(&"1".to_string()));
+    assert!(category_chain.is_descendant_of(&"2".to_string()));
+    assert!(!category_chain.is_descendant_of(&"3".to_string()));
+    assert!(!category_chain.is_descendant_of(&"4".to_string()));
+    assert!(category_chain.contains(&"3".to_string()));
+}
+
+#[test]
+fn displays_breadcrumb_of_names() {
+    let category_chain = build_from_definition_and_category(&build_definition(), &"2".to_string())
+        .expect("failed to build category chain");
+
+    assert_eq!("product > fruit", category_chain.to_string());
+    assert_eq!(
+        "1 > 2",
+        CategoryChain::new(vec!["1".to_string(), "2".to_string()]).to_string()
+    );
+}
+
+#[test]
+fn serializes_ids_and_names() {
+    let category_chain = build_from_definition_and_category(&build_definition(), &"2".to_string())
+        .expect("failed to build category chain");
+
+    assert_eq!(
+        serde_json::json!({
+            "category_chain": ["1", "2"],
+            "category_names": ["product", "fruit"]
+        }),
+        serde_json::to_value(&category_chain).expect("failed to serialize category chain")
+    );
+
+    let deserialized_category_chain: CategoryChain =
+        serde_json::from_str("{ \"category_chain\": [\"1\", \"2\"] }")
+            .expect("failed to deserialize category chain");
+
+    assert_eq!(category_chain, deserialized_category_chain);
+}
+
+#[test]
+fn unknown_category_is_reported() {
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        build_from_definition_and_category(&build_definition(), &"4".to_string())
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn dangling_parent_is_reported() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![
+            build_category("1", None, true, vec![]),
+            build_category("2", Some("7"), true, vec![]),
+        ],
+    );
+
+    let error = build_from_definition_and_category(&definition, &"2".to_string()).unwrap_err();
+
+    assert_eq!(ErrorKind::InvalidDefinition, error.kind());
+    assert!(error.message.contains("unknown parent '7'"));
+}
+
+#[test]
+fn cyclic_parents_are_reported() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![
+            build_category("1", Some("3"), true, vec![]),
+            build_category("2", Some("1"), true, vec![]),
+            build_category("3", Some("2"), true, vec![]),
+        ],
+    );
+
+    assert_eq!(
+        ErrorKind::InvalidDefinition,
+        build_from_definition_and_category(&definition, &"3".to_string())
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn deep_hierarchy_is_resolved_through_the_index() {
+    let mut categories = vec![build_category("0", None, true, vec![])];
+
+    for depth in 1..2000 {
+        let parent = (depth - 1).to_string();
+        categories.push(build_category(
+            &depth.to_string(),
+            Some(parent.as_str()),
+            true,
+            vec![],
+        ));
+    }
+
+    let category_index = CategoryIndex::new(&Definition::new("1".to_string(), categories));
+    let category_chain = category_index
+        .build_chain(&"1999".to_string())
+        .expect("failed to build category chain");
+
+    assert_eq!(2000, category_chain.depth());
+    assert_eq!(Some(&"0".to_string()), category_chain.root());
+}
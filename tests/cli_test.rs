@@ -0,0 +1,353 @@
+mod common;
+
+use std::env;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    cli::{run, CliOptions, ColorMode, OutputFormat, EXIT_FAILURE, EXIT_INVALID, EXIT_SUCCESS},
+    error::ErrorKind,
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_is_product_attribute, build_named_attribute, build_named_category};
+
+const VALID_VALUE: &str = "{ \"type\": \"1\", \"version\": \"1\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }";
+const INVALID_VALUE: &str = "{ \"type\": \"1\", \"version\": \"1\", \"11\": \"many\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }";
+
+fn build_definition() -> Definition {
+    let product_category = build_named_category(
+        "1",
+        "product",
+        None,
+        true,
+        vec![
+            build_named_attribute("11", "count", "integer", false),
+            build_is_product_attribute(),
+        ],
+    );
+
+    Definition::new("1".to_string(), vec![product_category])
+}
+
+/// Writes the file within a directory unique to the test.
+fn write_file(test_name: &str, file_name: &str, content: &str) -> String {
+    let directory: PathBuf = env::temp_dir().join(format!(
+        "cooplan-definition-schema-validator-{}-{}",
+        test_name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&directory).expect("failed to create test directory");
+
+    let path = directory.join(file_name);
+    fs::write(&path, content).expect("failed to write test file");
+
+    path.to_string_lossy().to_string()
+}
+
+fn write_definition(test_name: &str) -> String {
+    write_file(
+        test_name,
+        "definition.json",
+        &serde_json::to_string(&build_definition()).expect("failed to serialize definition"),
+    )
+}
+
+fn run_cli(args: Vec<String>, stdin: &str) -> (i32, String, String) {
+    let options = CliOptions::parse(args).expect("failed to parse arguments");
+    let mut output: Vec<u8> = Vec::new();
+    let mut error_output: Vec<u8> = Vec::new();
+
+    let exit_code = run(
+        &options,
+        &mut SchemaValidator::default(),
+        &mut stdin.as_bytes(),
+        &mut output,
+        &mut error_output,
+    );
+
+    (
+        exit_code,
+        String::from_utf8(output).expect("output is not UTF-8"),
+        String::from_utf8(error_output).expect("error output is not UTF-8"),
+    )
+}
+
+#[test]
+fn arguments_are_parsed() {
+    let options = CliOptions::parse(vec![
+        "--definition".to_string(),
+        "definition.yaml".to_string(),
+        "-f".to_string(),
+        "json".to_string(),
+        "--ndjson".to_string(),
+        "values.txt".to_string(),
+        "-".to_string(),
+    ])
+    .expect("failed to parse arguments");
+
+    assert_eq!("definition.yaml", options.definition);
+    assert_eq!(OutputFormat::Json, options.format);
+    assert!(options.ndjson);
+    assert_eq!(
+        vec!["values.txt".to_string(), "-".to_string()],
+        options.inputs
+    );
+
+    let options = CliOptions::parse(vec!["-d".to_string(), "definition.json".to_string()])
+        .expect("failed to parse arguments");
+    assert_eq!(vec!["-".to_string()], options.inputs);
+    assert_eq!(ColorMode::Auto, options.color);
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    for args in [
+        vec!["values.json"],
+        vec!["-d"],
+        vec!["-d", "definition.json", "--format", "xml"],
+        vec!["-d", "definition.json", "--strict"],
+        vec!["-d", "definition.json", "--color", "sometimes"],
+    ] {
+        assert_eq!(
+            ErrorKind::InvalidArgument,
+            CliOptions::parse(args.into_iter().map(str::to_string))
+                .unwrap_err()
+                .kind()
+        );
+    }
+}
+
+#[test]
+fn value_files_are_validated() {
+    let definition = write_definition("value_files");
+    let valid_value = write_file("value_files", "valid.json", VALID_VALUE);
+    let invalid_value = write_file("value_files", "invalid.json", INVALID_VALUE);
+
+    let (exit_code, output, _) = run_cli(
+        vec!["-d".to_string(), definition.clone(), valid_value.clone()],
+        "",
+    );
+    assert_eq!(EXIT_SUCCESS, exit_code);
+    assert!(output.contains(&format!("{}: ok", valid_value)));
+    assert!(output.ends_with("1 values validated, 0 invalid\n"));
+
+    let (exit_code, output, _) = run_cli(
+        vec![
+            "-d".to_string(),
+            definition,
+            valid_value,
+            invalid_value.clone(),
+        ],
+        "",
+    );
+    assert_eq!(EXIT_INVALID, exit_code);
+    assert!(output.contains(&format!(
+        "{}:1:38: InvalidValue (attribute '11'):",
+        invalid_value
+    )));
+    assert!(output.ends_with("2 values validated, 1 invalid\n"));
+}
+
+#[test]
+fn ndjson_stdin_is_validated_as_json_output() {
+    let definition = write_definition("ndjson_stdin");
+
+    let (exit_code, output, _) = run_cli(
+        vec![
+            "-d".to_string(),
+            definition,
+            "--ndjson".to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ],
+        &format!("{}\n\n{}\n", VALID_VALUE, INVALID_VALUE),
+    );
+
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("output line is not JSON"))
+        .collect();
+
+    assert_eq!(EXIT_INVALID, exit_code);
+    assert_eq!(3, lines.len());
+    assert_eq!("<stdin>:1", lines[0]["source"]);
+    assert_eq!(true, lines[0]["valid"]);
+    assert_eq!("<stdin>:3", lines[1]["source"]);
+    assert_eq!("InvalidValue", lines[1]["kind"]);
+    assert_eq!(3, lines[1]["line"]);
+    assert_eq!(38, lines[1]["column"]);
+    assert_eq!(1, lines[2]["summary"]["invalid"]);
+}
+
+#[test]
+fn ndjson_is_implied_by_extension() {
+    let definition = write_definition("ndjson_extension");
+    let values = write_file(
+        "ndjson_extension",
+        "values.ndjson",
+        &format!("{}\n{}\n", VALID_VALUE, VALID_VALUE),
+    );
+
+    let (exit_code, output, _) = run_cli(vec!["-d".to_string(), definition, values], "");
+
+    assert_eq!(EXIT_SUCCESS, exit_code);
+    assert!(output.ends_with("2 values validated, 0 invalid\n"));
+}
+
+#[test]
+fn unreadable_definition_fails() {
+    let (exit_code, output, error_output) = run_cli(
+        vec![
+            "-d".to_string(),
+            write_file(
+                "unreadable_definition",
+                "definition.json",
+                "{ \"version\": ",
+            ),
+        ],
+        VALID_VALUE,
+    );
+
+    assert_eq!(EXIT_FAILURE, exit_code);
+    assert!(output.is_empty());
+    assert!(error_output.starts_with("error: failed to deserialize definition"));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_definition_is_read() {
+    let definition = write_file(
+        "yaml_definition",
+        "definition.yaml",
+        "version: \"1\"
+categories:
+  - id: \"1\"
+    parent: null
+    parent_name: null
+    name: product
+    selectable_as_last: true
+    attributes:
+      - id: \"11\"
+        name: count
+        data_type: integer
+        unit: null
+        optional: false
+      - id: 4ed908eb-50b6-4faa-9baa-a7a897cec30f
+        name: IS_PRODUCT
+        data_type: boolean
+        unit: null
+        optional: false
+",
+    );
+
+    let (exit_code, _, _) = run_cli(vec!["-d".to_string(), definition], VALID_VALUE);
+
+    assert_eq!(EXIT_SUCCESS, exit_code);
+}
+
+#[test]
+fn junit_and_sarif_documents_are_written() {
+    let definition = write_definition("documents");
+    let values = write_file(
+        "documents",
+        "values.jsonl",
+        &format!("{}\n{}\n", VALID_VALUE, INVALID_VALUE),
+    );
+
+    let (exit_code, output, _) = run_cli(
+        vec![
+            "-d".to_string(),
+            definition.clone(),
+            "-f".to_string(),
+            "junit".to_string(),
+            values.clone(),
+        ],
+        "",
+    );
+    assert_eq!(EXIT_INVALID, exit_code);
+    assert!(output.contains("<testsuite name=\"cooplan-validate\" tests=\"2\" failures=\"1\">"));
+
+    let (exit_code, output, _) = run_cli(
+        vec![
+            "-d".to_string(),
+            definition,
+            "-f".to_string(),
+            "sarif".to_string(),
+            values,
+        ],
+        "",
+    );
+    let sarif: serde_json::Value = serde_json::from_str(&output).expect("output is not JSON");
+    assert_eq!(EXIT_INVALID, exit_code);
+    assert_eq!(1, sarif["runs"][0]["results"].as_array().unwrap().len());
+}
+
+#[test]
+fn invalid_values_are_rendered_as_pretty_diagnostics() {
+    let definition = write_definition("pretty");
+
+    let (exit_code, output, _) = run_cli(
+        vec![
+            "-d".to_string(),
+            definition,
+            "--ndjson".to_string(),
+            "--format".to_string(),
+            "pretty".to_string(),
+            "--color".to_string(),
+            "never".to_string(),
+        ],
+        &format!("{}\n{}\n", VALID_VALUE, INVALID_VALUE),
+    );
+
+    assert_eq!(EXIT_INVALID, exit_code);
+    assert!(output.starts_with("<stdin>:1: ok\n"));
+    assert!(output.contains(" --> <stdin>:2:38\n"));
+    assert!(output.contains("^^^^^^ expected integer (count)\n"));
+    assert!(!output.contains('\x1b'));
+    assert!(output.ends_with("2 values validated, 1 invalid\n"));
+}
+
+/// Stream failing once its first bytes have been read, like a producer still running.
+struct InterruptedStream;
+
+impl std::io::Read for InterruptedStream {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "stream interrupted",
+        ))
+    }
+}
+
+#[test]
+fn ndjson_stdin_is_validated_line_by_line() {
+    let definition = write_definition("ndjson_streaming");
+    let options = CliOptions::parse(vec!["-d".to_string(), definition, "--ndjson".to_string()])
+        .expect("failed to parse arguments");
+
+    let first_line = format!("{}\n", VALID_VALUE);
+    let mut stdin = BufReader::new(first_line.as_bytes().chain(InterruptedStream));
+    let mut output: Vec<u8> = Vec::new();
+    let mut error_output: Vec<u8> = Vec::new();
+
+    let exit_code = run(
+        &options,
+        &mut SchemaValidator::default(),
+        &mut stdin,
+        &mut output,
+        &mut error_output,
+    );
+
+    assert_eq!(EXIT_FAILURE, exit_code);
+    assert_eq!(
+        "<stdin>:1: ok\n",
+        String::from_utf8(output).expect("output is not UTF-8")
+    );
+    assert!(String::from_utf8(error_output)
+        .expect("error output is not UTF-8")
+        .contains("stream interrupted"));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+    validated_source_category::ValidatedSourceCategory,
+};
+
+use cooplan_definition_schema_validator::{
+    codegen::StructGenerator, error::ErrorKind, schema_validator::SchemaValidator,
+};
+
+use common::{build_category, build_named_attribute, build_product_definition};
+
+mod generated {
+    include!("generated/fruit.rs");
+}
+
+fn build_definition() -> Definition {
+    let price_per_kg_attribute = ValidatedSourceAttribute {
+        unit: Some("EUR/kg".to_string()),
+        ..build_named_attribute("12", "price_per_kg", "decimal", false)
+    };
+
+    build_product_definition(
+        vec![
+            build_named_attribute("10", "product_name", "string", false),
+            build_named_attribute("11", "count", "integer", false),
+        ],
+        vec![
+            price_per_kg_attribute,
+            build_named_attribute("13", "type", "string", true),
+        ],
+    )
+}
+
+#[test]
+fn generates_structs_for_selectable_categories() {
+    let code = StructGenerator::default()
+        .generate(&build_definition())
+        .expect("failed to generate structs");
+
+    assert_eq!(include_str!("generated/fruit.rs"), code);
+    assert!(!code.contains("pub struct Product"));
+}
+
+#[test]
+fn generated_struct_converts_from_definition_value() {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"2\", \"version\": \"1\", \"10\": \"Pear\", \"11\": 600, \"12\": 15.39, \"13\": \"Conference\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    let definition_value = SchemaValidator::default()
+        .validate(json_value_string, build_definition())
+        .expect("failed to validate fruit");
+
+    let fruit = generated::Fruit::try_from(definition_value).expect("failed to convert fruit");
+
+    assert_eq!("Pear", fruit.product_name);
+    assert_eq!(600, fruit.count);
+    assert_eq!(15.39, fruit.price_per_kg);
+    assert_eq!(Some("Conference".to_string()), fruit.r#type);
+    assert!(fruit.is_product);
+}
+
+#[test]
+fn unregistered_data_type_is_reported() {
+    let mut definition = build_definition();
+    let mut categories = definition.categories();
+    categories[1].attributes[0].data_type = "money".to_string();
+    definition = Definition::new(definition.version(), categories);
+
+    assert_eq!(
+        ErrorKind::DataTypeNotRegistered,
+        StructGenerator::default()
+            .generate(&definition)
+            .unwrap_err()
+            .kind()
+    );
+
+    let mut struct_generator = StructGenerator::default();
+    struct_generator.register_rust_type("money".to_string(), "f64".to_string());
+
+    assert!(struct_generator.generate(&definition).is_ok());
+}
+
+#[test]
+fn colliding_and_reserved_identifiers_are_renamed() {
+    let mut categories = build_definition().categories();
+    categories[1].attributes.extend([
+        build_named_attribute("14", "self", "string", false),
+        build_named_attribute("15", "価格", "decimal", false),
+        build_named_attribute("16", "_", "boolean", true),
+    ]);
+
+    for (id, name) in [("4", "fruit category 3"), ("3", "fruit"), ("5", "self")] {
+        categories.push(ValidatedSourceCategory {
+            name: name.to_string(),
+            ..build_category(id, Some("1"), true, vec![])
+        });
+    }
+
+    let code = StructGenerator::default()
+        .generate(&Definition::new("1".to_string(), categories))
+        .expect("failed to generate structs");
+
+    assert!(code.contains("    pub self_: String,"));
+    assert!(code.contains("    pub attribute_15: f64,"));
+    assert!(code.contains("    pub attribute_16: Option<bool>,"));
+    assert!(code.contains("    pub r#type: Option<String>,"));
+    assert!(code.contains("pub struct Fruit {"));
+    assert!(code.contains("pub struct FruitCategory3 {"));
+    assert!(code.contains("pub struct FruitCategory32 {"));
+    assert!(code.contains("pub struct Self_ {"));
+}
@@ -0,0 +1,104 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+    validated_source_category::ValidatedSourceCategory,
+};
+
+/// Id of the marker attribute flagging values as products.
+pub const IS_PRODUCT_ID: &str = "4ed908eb-50b6-4faa-9baa-a7a897cec30f";
+
+/// Id of the marker attribute flagging values as services.
+pub const IS_SERVICE_ID: &str = "70b0d023-20e6-45cb-9654-e5fd42749642";
+
+pub fn build_attribute(id: &str, data_type: &str, optional: bool) -> ValidatedSourceAttribute {
+    build_named_attribute(id, &format!("attribute_{}", id), data_type, optional)
+}
+
+pub fn build_named_attribute(
+    id: &str,
+    name: &str,
+    data_type: &str,
+    optional: bool,
+) -> ValidatedSourceAttribute {
+    ValidatedSourceAttribute {
+        id: id.to_string(),
+        name: name.to_string(),
+        data_type: data_type.to_string(),
+        unit: None,
+        optional,
+    }
+}
+
+pub fn build_is_product_attribute() -> ValidatedSourceAttribute {
+    ValidatedSourceAttribute {
+        id: IS_PRODUCT_ID.to_string(),
+        name: "IS_PRODUCT".to_string(),
+        data_type: "boolean".to_string(),
+        unit: None,
+        optional: false,
+    }
+}
+
+pub fn build_category(
+    id: &str,
+    parent: Option<&str>,
+    selectable_as_last: bool,
+    attributes: Vec<ValidatedSourceAttribute>,
+) -> ValidatedSourceCategory {
+    build_named_category(
+        id,
+        &format!("category_{}", id),
+        parent,
+        selectable_as_last,
+        attributes,
+    )
+}
+
+pub fn build_named_category(
+    id: &str,
+    name: &str,
+    parent: Option<&str>,
+    selectable_as_last: bool,
+    attributes: Vec<ValidatedSourceAttribute>,
+) -> ValidatedSourceCategory {
+    ValidatedSourceCategory {
+        id: id.to_string(),
+        parent: parent.map(str::to_string),
+        parent_name: None,
+        name: name.to_string(),
+        selectable_as_last,
+        attributes,
+    }
+}
+
+/// Definition of version "1" made of the root "product" category (id "1"), holding
+/// the given attributes alongside the `IS_PRODUCT` marker, and of its selectable
+/// "fruit" child category (id "2").
+pub fn build_product_definition(
+    mut product_attributes: Vec<ValidatedSourceAttribute>,
+    fruit_attributes: Vec<ValidatedSourceAttribute>,
+) -> Definition {
+    product_attributes.push(build_is_product_attribute());
+
+    let product_category: ValidatedSourceCategory = ValidatedSourceCategory {
+        id: "1".to_string(),
+        parent: None,
+        parent_name: None,
+        name: "product".to_string(),
+        selectable_as_last: false,
+        attributes: product_attributes,
+    };
+
+    let fruit_category: ValidatedSourceCategory = ValidatedSourceCategory {
+        id: "2".to_string(),
+        parent: Some("1".to_string()),
+        parent_name: Some("product".to_string()),
+        name: "fruit".to_string(),
+        selectable_as_last: true,
+        attributes: fruit_attributes,
+    };
+
+    Definition::new("1".to_string(), vec![product_category, fruit_category])
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::definition_diff::{
+    diff, DefinitionChange, DefinitionChangeKind,
+};
+
+use common::{build_attribute, build_category};
+
+fn build_old_definition() -> Definition {
+    Definition::new(
+        "1".to_string(),
+        vec![
+            build_category(
+                "1",
+                None,
+                false,
+                vec![
+                    build_attribute("10", "string", false),
+                    build_attribute("11", "integer", true),
+                ],
+            ),
+            build_category(
+                "2",
+                Some("1"),
+                true,
+                vec![build_attribute("12", "decimal", false)],
+            ),
+            build_category("3", Some("1"), true, vec![]),
+        ],
+    )
+}
+
+#[test]
+fn identical_definitions_have_no_changes() {
+    let definition_diff =
+        diff(&build_old_definition(), &build_old_definition()).expect("failed to diff");
+
+    assert!(definition_diff.changes.is_empty());
+    assert!(!definition_diff.is_breaking());
+}
+
+#[test]
+fn non_breaking_changes_are_classified() {
+    let new_definition = Definition::new(
+        "2".to_string(),
+        vec![
+            build_category(
+                "1",
+                None,
+                true,
+                vec![
+                    build_attribute("10", "string", true),
+                    build_attribute("11", "integer", true),
+                ],
+            ),
+            build_category(
+                "2",
+                Some("1"),
+                true,
+                vec![
+                    build_attribute("12", "decimal", false),
+                    build_attribute("13", "string", true),
+                ],
+            ),
+            build_category("3", Some("1"), true, vec![]),
+            build_category("4", Some("1"), true, vec![]),
+        ],
+    );
+
+    let definition_diff = diff(&build_old_definition(), &new_definition).expect("failed to diff");
+
+    assert!(!definition_diff.is_breaking());
+    assert!(definition_diff.changes.contains(&DefinitionChange {
+        category_id: "2".to_string(),
+        kind: DefinitionChangeKind::AttributeOptionalChanged {
+            attribute_id: "10".to_string(),
+            old_optional: false,
+            new_optional: true,
+        },
+        breaking: false,
+    }));
+    assert!(definition_diff.changes.contains(&DefinitionChange {
+        category_id: "2".to_string(),
+        kind: DefinitionChangeKind::AttributeAdded {
+            attribute_id: "13".to_string(),
+            optional: true,
+        },
+        breaking: false,
+    }));
+    assert!(definition_diff.changes.contains(&DefinitionChange {
+        category_id: "4".to_string(),
+        kind: DefinitionChangeKind::CategoryAdded,
+        breaking: false,
+    }));
+}
+
+#[test]
+fn breaking_changes_are_classified_through_the_parent_chain() {
+    let new_definition = Definition::new(
+        "2".to_string(),
+        vec![
+            build_category(
+                "1",
+                None,
+                false,
+                vec![
+                    build_attribute("10", "integer", false),
+                    build_attribute("11", "integer", false),
+                ],
+            ),
+            build_category(
+                "2",
+                None,
+                false,
+                vec![build_attribute("12", "decimal", false)],
+            ),
+        ],
+    );
+
+    let definition_diff = diff(&build_old_definition(), &new_definition).expect("failed to diff");
+    let breaking_changes: Vec<&DefinitionChange> = definition_diff.breaking_changes().collect();
+
+    assert!(definition_diff.is_breaking());
+    assert!(breaking_changes.contains(&&DefinitionChange {
+        category_id: "2".to_string(),
+        kind: DefinitionChangeKind::CategoryReparented {
+            old_parent: Some("1".to_string()),
+            new_parent: None,
+        },
+        breaking: true,
+    }));
+    assert!(breaking_changes.contains(&&DefinitionChange {
+        category_id: "2".to_string(),
+        kind: DefinitionChangeKind::SelectableAsLastChanged {
+            old_selectable_as_last: true,
+            new_selectable_as_last: false,
+        },
+        breaking: true,
+    }));
+    assert!(breaking_changes.contains(&&DefinitionChange {
+        category_id: "1".to_string(),
+        kind: DefinitionChangeKind::AttributeRetyped {
+            attribute_id: "10".to_string(),
+            old_data_type: "string".to_string(),
+            new_data_type: "integer".to_string(),
+        },
+        breaking: true,
+    }));
+    assert!(breaking_changes.contains(&&DefinitionChange {
+        category_id: "1".to_string(),
+        kind: DefinitionChangeKind::AttributeOptionalChanged {
+            attribute_id: "11".to_string(),
+            old_optional: true,
+            new_optional: false,
+        },
+        breaking: true,
+    }));
+    assert!(breaking_changes.contains(&&DefinitionChange {
+        category_id: "3".to_string(),
+        kind: DefinitionChangeKind::CategoryRemoved,
+        breaking: true,
+    }));
+    assert!(definition_diff.changes.contains(&DefinitionChange {
+        category_id: "2".to_string(),
+        kind: DefinitionChangeKind::AttributeRemoved {
+            attribute_id: "10".to_string(),
+        },
+        breaking: false,
+    }));
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    definition_registry::{DefinitionRegistry, FallbackPolicy},
+    error::ErrorKind,
+    schema_validator::SchemaValidator,
+    version_policy::VersionPolicy,
+};
+
+use common::{build_category, build_is_product_attribute, build_named_attribute};
+
+fn build_definition(version: &str, count_data_type: &str) -> Definition {
+    let product_category = build_category(
+        "1",
+        None,
+        true,
+        vec![
+            build_named_attribute("11", "count", count_data_type, false),
+            build_is_product_attribute(),
+        ],
+    );
+
+    Definition::new(version.to_string(), vec![product_category])
+}
+
+fn build_registry() -> DefinitionRegistry {
+    let mut definition_registry = DefinitionRegistry::default();
+
+    definition_registry.register_definition(build_definition("1.0.0-A", "string"));
+    definition_registry.register_definition(build_definition("1.1.0", "integer"));
+
+    definition_registry
+}
+
+#[test]
+fn picks_definition_from_value_version() {
+    let definition_registry = build_registry();
+    let mut schema_validator = SchemaValidator::default();
+
+    let definition_value = definition_registry
+        .validate(
+            &mut schema_validator,
+            String::from(
+                "{ \"type\": \"1\", \"version\": \"1.0.0-a\", \"11\": \"600\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+            ),
+        )
+        .expect("failed to validate value of version 1.0.0-a");
+    assert_eq!("1.0.0-A", definition_value.definition());
+
+    let definition_value = definition_registry
+        .validate(
+            &mut schema_validator,
+            String::from(
+                "{ \"type\": \"1\", \"version\": \"1.1.0\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+            ),
+        )
+        .expect("failed to validate value of version 1.1.0");
+    assert_eq!("1.1.0", definition_value.definition());
+}
+
+#[test]
+fn unknown_version_is_rejected_by_default() {
+    let definition_registry = build_registry();
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::UnknownDefinitionVersion,
+        definition_registry
+            .validate(
+                &mut schema_validator,
+                String::from(
+                    "{ \"type\": \"1\", \"version\": \"1.0.5\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+                ),
+            )
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn unknown_version_falls_back_to_configured_definition() {
+    let mut definition_registry = build_registry();
+    definition_registry.set_fallback_policy(FallbackPolicy::LatestRegistered);
+
+    let mut schema_validator = SchemaValidator::default();
+    let value = String::from(
+        "{ \"type\": \"1\", \"version\": \"1.0.5\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    assert_eq!(
+        ErrorKind::UnknownDefinitionVersion,
+        definition_registry
+            .validate(&mut schema_validator, value.clone())
+            .unwrap_err()
+            .kind()
+    );
+
+    schema_validator.set_version_policy(VersionPolicy::SemverCompatible);
+
+    let definition_value = definition_registry
+        .validate(&mut schema_validator, value)
+        .expect("failed to validate value through the fallback definition");
+    assert_eq!("1.1.0", definition_value.definition());
+
+    definition_registry.set_fallback_policy(FallbackPolicy::Version("1.0.0-a".to_string()));
+    assert_eq!(
+        "1.0.0-A",
+        definition_registry.resolve("0.9").unwrap().version()
+    );
+}
+
+#[test]
+fn removing_latest_definition_falls_back_to_previous_one() {
+    let mut definition_registry = build_registry();
+    definition_registry.set_fallback_policy(FallbackPolicy::LatestRegistered);
+    definition_registry.register_definition(build_definition("1.2.0", "integer"));
+
+    assert!(definition_registry.remove_definition("1.2.0").is_some());
+    assert_eq!(
+        "1.1.0",
+        definition_registry.resolve("0.9").unwrap().version()
+    );
+
+    definition_registry.register_definition(build_definition("1.0.0-A", "string"));
+    assert_eq!(
+        "1.0.0-A",
+        definition_registry.resolve("0.9").unwrap().version()
+    );
+
+    definition_registry.remove_definition("1.0.0-a");
+    definition_registry.remove_definition("1.1.0");
+    assert_eq!(
+        ErrorKind::UnknownDefinitionVersion,
+        definition_registry.resolve("0.9").unwrap_err().kind()
+    );
+}
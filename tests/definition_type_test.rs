@@ -0,0 +1,243 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    definition_type::{DefinitionType, DefinitionTypeMarkerSource, DefinitionTypeMarkers},
+    error::ErrorKind,
+    schema_validator::SchemaValidator,
+};
+
+use common::{
+    build_category, build_is_product_attribute, build_named_attribute, IS_PRODUCT_ID, IS_SERVICE_ID,
+};
+
+fn build_definition() -> Definition {
+    let product_category = build_category("1", None, true, vec![build_is_product_attribute()]);
+    let bundle_category = build_category(
+        "2",
+        None,
+        true,
+        vec![
+            build_is_product_attribute(),
+            build_named_attribute(IS_SERVICE_ID, "IS_SERVICE", "boolean", false),
+        ],
+    );
+    let subscription_category = build_category(
+        "3",
+        None,
+        true,
+        vec![build_named_attribute(
+            "30",
+            "IS_SUBSCRIPTION",
+            "boolean",
+            false,
+        )],
+    );
+
+    Definition::new(
+        "1".to_string(),
+        vec![product_category, bundle_category, subscription_category],
+    )
+}
+
+#[test]
+fn false_marker_is_rejected() {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"1\", \"version\": \"1\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": false }",
+    );
+
+    let error = SchemaValidator::default()
+        .validate(json_value_string, build_definition())
+        .unwrap_err();
+
+    assert_eq!(ErrorKind::InvalidValue, error.kind());
+    assert!(error.message.contains("is false"));
+}
+
+#[test]
+fn conflicting_markers_are_rejected() {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"2\", \"version\": \"1\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true, \"70b0d023-20e6-45cb-9654-e5fd42749642\": true }",
+    );
+
+    assert_eq!(
+        ErrorKind::DefinitionTypeConflict,
+        SchemaValidator::default()
+            .validate(json_value_string, build_definition())
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn configured_custom_marker_is_detected() {
+    let json_value_string: String =
+        String::from("{ \"type\": \"3\", \"version\": \"1\", \"30\": true }");
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        schema_validator
+            .validate(json_value_string.clone(), build_definition())
+            .unwrap_err()
+            .kind()
+    );
+
+    let mut definition_type_markers = DefinitionTypeMarkers::default();
+    definition_type_markers.register_marker(
+        "30".to_string(),
+        DefinitionType::Custom("subscription".to_string()),
+    );
+    schema_validator.set_definition_type_marker_source(DefinitionTypeMarkerSource::Configured(
+        definition_type_markers,
+    ));
+
+    let definition_value = schema_validator
+        .validate(json_value_string, build_definition())
+        .expect("failed to validate value of a custom definition type");
+
+    assert_eq!(
+        &DefinitionType::Custom("subscription".to_string()),
+        definition_value.definition_type()
+    );
+}
+
+#[test]
+fn markers_are_read_from_definition() {
+    let marker_attribute_ids = vec![
+        "4ed908eb-50b6-4faa-9baa-a7a897cec30f".to_string(),
+        "30".to_string(),
+    ];
+    let definition_type_markers =
+        DefinitionTypeMarkers::from_definition(&build_definition(), &marker_attribute_ids);
+
+    assert_eq!(
+        Some(&"4ed908eb-50b6-4faa-9baa-a7a897cec30f".to_string()),
+        definition_type_markers.marker_for(&DefinitionType::Product)
+    );
+    assert_eq!(
+        Some(&"30".to_string()),
+        definition_type_markers.marker_for(&DefinitionType::Custom("subscription".to_string()))
+    );
+    assert_eq!(
+        None,
+        definition_type_markers.marker_for(&DefinitionType::Service)
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+    schema_validator.set_definition_type_marker_source(DefinitionTypeMarkerSource::Definition(
+        marker_attribute_ids,
+    ));
+
+    let definition_value = schema_validator
+        .validate(
+            String::from("{ \"type\": \"3\", \"version\": \"1\", \"30\": true }"),
+            build_definition(),
+        )
+        .expect("failed to validate value with a marker from the definition");
+
+    assert_eq!(
+        &DefinitionType::Custom("subscription".to_string()),
+        definition_value.definition_type()
+    );
+}
+
+#[test]
+fn undeclared_boolean_attributes_are_not_markers() {
+    let mut categories = build_definition().categories();
+    categories[0]
+        .attributes
+        .push(build_named_attribute("20", "IS_ORGANIC", "boolean", false));
+    let definition = Definition::new("1".to_string(), categories);
+
+    let mut schema_validator = SchemaValidator::default();
+    schema_validator.set_definition_type_marker_source(DefinitionTypeMarkerSource::Definition(
+        vec!["4ed908eb-50b6-4faa-9baa-a7a897cec30f".to_string()],
+    ));
+
+    for is_organic in [false, true] {
+        let definition_value = schema_validator
+            .validate(
+                format!(
+                    "{{ \"type\": \"1\", \"version\": \"1\", \"20\": {}, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }}",
+                    is_organic
+                ),
+                definition.clone(),
+            )
+            .expect("failed to validate value with a regular IS_ boolean");
+
+        assert_eq!(&DefinitionType::Product, definition_value.definition_type());
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn built_in_definition_types_keep_their_attribute_id() {
+    assert_eq!(Some(IS_PRODUCT_ID), DefinitionType::Product.attribute_id());
+    assert_eq!(Some(IS_SERVICE_ID), DefinitionType::Service.attribute_id());
+    assert_eq!(
+        None,
+        DefinitionType::Custom("subscription".to_string()).attribute_id()
+    );
+}
+
+fn build_rooted_definition() -> Definition {
+    let service_category = build_category("5", None, false, vec![]);
+    let delivery_category = build_category(
+        "6",
+        Some("5"),
+        true,
+        vec![build_named_attribute("60", "distance", "decimal", false)],
+    );
+
+    Definition::new("1".to_string(), vec![service_category, delivery_category])
+}
+
+#[test]
+fn definition_type_is_inferred_from_root_category() {
+    let json_value_string: String =
+        String::from("{ \"type\": \"6\", \"version\": \"1\", \"60\": 4.5 }");
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        schema_validator
+            .validate(json_value_string.clone(), build_rooted_definition())
+            .unwrap_err()
+            .kind()
+    );
+
+    schema_validator.register_definition_type_root("5".to_string(), DefinitionType::Service);
+
+    let definition_value = schema_validator
+        .validate(json_value_string, build_rooted_definition())
+        .expect("failed to validate value under a registered root category");
+
+    assert_eq!(&DefinitionType::Service, definition_value.definition_type());
+}
+
+#[test]
+fn marker_disagreeing_with_root_category_is_rejected() {
+    let mut definition = build_rooted_definition();
+    let mut categories = definition.categories();
+    categories[0].attributes.push(build_is_product_attribute());
+    definition = Definition::new(definition.version(), categories);
+
+    let json_value_string: String = String::from(
+        "{ \"type\": \"6\", \"version\": \"1\", \"60\": 4.5, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+    schema_validator.register_definition_type_root("5".to_string(), DefinitionType::Service);
+
+    assert_eq!(
+        ErrorKind::DefinitionTypeConflict,
+        schema_validator
+            .validate(json_value_string, definition)
+            .unwrap_err()
+            .kind()
+    );
+}
@@ -0,0 +1,212 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    definition_value::DefinitionValue, error::ErrorKind, schema_validator::SchemaValidator,
+};
+
+use common::{build_named_attribute, build_product_definition};
+
+fn build_definition() -> Definition {
+    build_product_definition(
+        vec![
+            build_named_attribute("10", "product_name", "string", false),
+            build_named_attribute("11", "count", "integer", false),
+        ],
+        vec![build_named_attribute(
+            "12",
+            "price_per_kg",
+            "decimal",
+            false,
+        )],
+    )
+}
+
+fn validate_fruit() -> DefinitionValue {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"2\", \"version\": \"1\", \"10\": \"Pear\", \"11\": 600, \"12\": 15.39, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    SchemaValidator::default()
+        .validate(json_value_string, build_definition())
+        .expect("failed to validate fruit")
+}
+
+#[test]
+fn serialized_value_is_validated_on_deserialization() {
+    let serialized_value =
+        serde_json::to_string(&validate_fruit()).expect("failed to serialize value");
+
+    let mut schema_validator = SchemaValidator::default();
+
+    let definition_value = DefinitionValue::deserialize_and_validate(
+        serialized_value.as_str(),
+        &mut schema_validator,
+        build_definition(),
+    )
+    .expect("failed to deserialize a valid value");
+
+    assert_eq!("1", definition_value.definition());
+    assert!(definition_value.category_chain().contains(&"1".to_string()));
+    assert!(definition_value.category_chain().contains(&"2".to_string()));
+    assert_eq!(
+        Some(&"2".to_string()),
+        definition_value.category_chain().leaf()
+    );
+}
+
+#[test]
+fn trusted_value_is_deserialized_without_validation() {
+    let mut serialized_value =
+        serde_json::to_value(validate_fruit()).expect("failed to serialize value");
+    serialized_value["value"]["11"] = serde_json::Value::String("many".to_string());
+
+    let definition_value = DefinitionValue::from_trusted_json(&serialized_value.to_string())
+        .expect("failed to deserialize a trusted value");
+
+    assert_eq!("many", definition_value.value()["11"]);
+    assert_eq!(
+        ErrorKind::DeserializationFailure,
+        DefinitionValue::from_trusted_json("{ \"definition\": \"1\" }")
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn forged_attribute_is_rejected_on_deserialization() {
+    let mut serialized_value =
+        serde_json::to_value(validate_fruit()).expect("failed to serialize value");
+    serialized_value["value"]["11"] = serde_json::Value::String("many".to_string());
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        DefinitionValue::deserialize_and_validate(
+            serialized_value.to_string().as_str(),
+            &mut schema_validator,
+            build_definition(),
+        )
+        .unwrap_err()
+        .kind()
+    );
+}
+
+#[test]
+fn forged_definition_type_is_rejected_on_deserialization() {
+    let mut serialized_value =
+        serde_json::to_value(validate_fruit()).expect("failed to serialize value");
+    serialized_value["definition_type"] = serde_json::Value::String("Service".to_string());
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        DefinitionValue::deserialize_and_validate(
+            serialized_value.to_string().as_str(),
+            &mut schema_validator,
+            build_definition(),
+        )
+        .unwrap_err()
+        .kind()
+    );
+}
+
+#[test]
+fn forged_category_chain_is_rejected_on_deserialization() {
+    let mut serialized_value =
+        serde_json::to_value(validate_fruit()).expect("failed to serialize value");
+    serialized_value["category_chain"]["category_chain"] = serde_json::json!(["2"]);
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        DefinitionValue::deserialize_and_validate(
+            serialized_value.to_string().as_str(),
+            &mut schema_validator,
+            build_definition(),
+        )
+        .unwrap_err()
+        .kind()
+    );
+}
+
+#[test]
+fn foreign_definition_version_is_rejected_on_deserialization() {
+    let mut serialized_value =
+        serde_json::to_value(validate_fruit()).expect("failed to serialize value");
+    serialized_value["definition"] = serde_json::Value::String("2".to_string());
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::ValueDefinitionMismatch,
+        DefinitionValue::deserialize_and_validate(
+            serialized_value.to_string().as_str(),
+            &mut schema_validator,
+            build_definition(),
+        )
+        .unwrap_err()
+        .kind()
+    );
+}
+
+#[test]
+fn typed_accessors_read_attributes_by_id_and_name() {
+    let definition_value = validate_fruit();
+
+    assert_eq!("Pear", definition_value.get_str("10").unwrap());
+    assert_eq!("Pear", definition_value.get_str("product_name").unwrap());
+    assert_eq!(600, definition_value.get_i64("count").unwrap());
+    assert_eq!(15.39, definition_value.get_decimal("12").unwrap());
+    assert!(definition_value.get_bool("IS_PRODUCT").unwrap());
+}
+
+#[test]
+fn typed_accessors_detect_type_mismatch() {
+    let definition_value = validate_fruit();
+
+    assert_eq!(
+        ErrorKind::AttributeTypeMismatch,
+        definition_value.get_i64("product_name").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::AttributeTypeMismatch,
+        definition_value.get_bool("11").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::AttributeTypeMismatch,
+        definition_value.get_decimal("count").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::AttributeTypeMismatch,
+        definition_value.get_i64("price_per_kg").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::AttributeNotFound,
+        definition_value.get_str("colour").unwrap_err().kind()
+    );
+}
+
+#[test]
+fn attributes_iterate_with_their_metadata() {
+    let definition_value = validate_fruit();
+
+    let mut attribute_names: Vec<&str> = definition_value
+        .attributes()
+        .map(|(attribute, _)| attribute.name.as_str())
+        .collect();
+    attribute_names.sort();
+
+    assert_eq!(
+        vec!["IS_PRODUCT", "count", "price_per_kg", "product_name"],
+        attribute_names
+    );
+
+    for (attribute, attribute_value) in definition_value.attributes() {
+        assert_eq!(&definition_value.value()[&attribute.id], attribute_value);
+    }
+}
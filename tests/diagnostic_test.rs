@@ -0,0 +1,156 @@
+mod common;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+
+use cooplan_definition_schema_validator::{
+    diagnostic::DiagnosticRenderer, report::ValidationOutcome, schema_validator::SchemaValidator,
+    validations::validate_decimal,
+};
+
+use common::{build_named_attribute, build_product_definition};
+
+fn build_definition() -> Definition {
+    let price_attribute = ValidatedSourceAttribute {
+        unit: Some("EUR".to_string()),
+        ..build_named_attribute("12", "price", "price", false)
+    };
+
+    build_product_definition(
+        vec![
+            build_named_attribute("11", "count", "integer", false),
+            price_attribute,
+        ],
+        vec![],
+    )
+}
+
+fn build_schema_validator() -> SchemaValidator {
+    let mut schema_validator = SchemaValidator::default();
+    schema_validator.register_validation("price".to_string(), Box::new(validate_decimal));
+
+    schema_validator
+}
+
+fn render(
+    diagnostic_renderer: &DiagnosticRenderer,
+    source_name: &str,
+    line: Option<usize>,
+    source: &str,
+) -> Option<String> {
+    let outcome = ValidationOutcome::new(
+        source_name,
+        line,
+        build_schema_validator().validate(source.to_string(), build_definition()),
+    );
+
+    diagnostic_renderer.render(&outcome, source, &build_definition())
+}
+
+#[test]
+fn offending_value_is_underlined_and_labelled() {
+    let diagnostic = render(
+        &DiagnosticRenderer::default(),
+        "apple.json",
+        None,
+        "{\n  \"type\": \"2\",\n  \"version\": \"1\",\n  \"11\": \"many\",\n  \"12\": 1.5\n}",
+    )
+    .expect("invalid value has no diagnostic");
+
+    assert_eq!(
+        "error[InvalidValue]: failed to convert attribute value to integer\n \
+         --> apple.json:4:9\n  \
+         |\n\
+         4 |   \"11\": \"many\",\n  \
+         |         ^^^^^^ expected integer (count)\n  \
+         |\n  \
+         = help: integer values are whole numbers from -9223372036854775808 to 9223372036854775807\n",
+        diagnostic
+    );
+
+    assert!(render(
+        &DiagnosticRenderer::default(),
+        "apple.json",
+        None,
+        "{ \"type\": \"2\", \"version\": \"1\", \"11\": 3, \"12\": 1.5, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    )
+    .is_none());
+}
+
+#[test]
+fn help_shows_registered_range_and_unit() {
+    let mut diagnostic_renderer = DiagnosticRenderer::default();
+    diagnostic_renderer.register_data_type_help(
+        "price".to_string(),
+        "price values range from 0 to 10000".to_string(),
+    );
+
+    let diagnostic = render(
+        &diagnostic_renderer,
+        "values.ndjson",
+        Some(12),
+        "{ \"type\": \"2\", \"version\": \"1\", \"11\": 3, \"12\": \"free\" }",
+    )
+    .expect("invalid value has no diagnostic");
+
+    assert!(diagnostic.contains(" --> values.ndjson:12:47\n"));
+    assert!(diagnostic
+        .contains("12 | { \"type\": \"2\", \"version\": \"1\", \"11\": 3, \"12\": \"free\" }\n"));
+    assert!(diagnostic.contains("^^^^^^ expected price (price)\n"));
+    assert!(diagnostic.contains("   = help: price values range from 0 to 10000\n"));
+    assert!(diagnostic.contains("   = help: price is measured in EUR\n"));
+
+    let diagnostic = render(
+        &diagnostic_renderer,
+        "apple.json",
+        None,
+        "{\n  \"type\": \"2\",\n  \"version\": \"1\",\n  \"11\": 3\n}",
+    )
+    .expect("value missing an attribute has no diagnostic");
+
+    assert!(diagnostic.contains("1 | {\n  | ^ missing price (price)\n"));
+}
+
+#[test]
+fn unknown_category_lists_selectable_categories() {
+    let diagnostic = render(
+        &DiagnosticRenderer::default(),
+        "apple.json",
+        None,
+        "{ \"type\": \"7\", \"version\": \"1\" }",
+    )
+    .expect("value of unknown category has no diagnostic");
+
+    assert!(diagnostic.contains("  |           ^^^\n"));
+    assert!(diagnostic.contains(
+        "  = help: \"type\" is one of the categories selectable as last: \"2\" (fruit)\n"
+    ));
+}
+
+#[test]
+fn diagnostics_are_colored_for_terminals() {
+    let mut diagnostic_renderer = DiagnosticRenderer::default();
+    diagnostic_renderer.set_colored(true);
+
+    let diagnostic = render(
+        &diagnostic_renderer,
+        "apple.json",
+        None,
+        "{ \"type\": \"2\", \"version\": \"1\", \"11\": true, \"12\": 1.5 }",
+    )
+    .expect("invalid value has no diagnostic");
+
+    assert!(diagnostic.starts_with("\x1b[1;31merror[InvalidValue]\x1b[0m"));
+    assert!(diagnostic.contains("\x1b[1;31m^^^^ expected integer (count)\x1b[0m"));
+
+    let plain_diagnostic = render(
+        &DiagnosticRenderer::default(),
+        "apple.json",
+        None,
+        "{ \"type\": \"2\", \"version\": \"1\", \"11\": true, \"12\": 1.5 }",
+    )
+    .expect("invalid value has no diagnostic");
+
+    assert!(!plain_diagnostic.contains('\x1b'));
+}
@@ -0,0 +1,44 @@
+// Generated from definition version '1'. Do not edit by hand.
+
+/// fruit (category '2').
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Fruit {
+    /// price_per_kg (EUR/kg).
+    #[serde(rename = "12")]
+    pub price_per_kg: f64,
+    /// type.
+    #[serde(rename = "13", default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    /// product_name.
+    #[serde(rename = "10")]
+    pub product_name: String,
+    /// count.
+    #[serde(rename = "11")]
+    pub count: i64,
+    /// IS_PRODUCT.
+    #[serde(rename = "4ed908eb-50b6-4faa-9baa-a7a897cec30f")]
+    pub is_product: bool,
+}
+
+impl TryFrom<cooplan_definition_schema_validator::definition_value::DefinitionValue> for Fruit {
+    type Error = cooplan_definition_schema_validator::error::Error;
+
+    fn try_from(
+        value: cooplan_definition_schema_validator::definition_value::DefinitionValue,
+    ) -> Result<Self, Self::Error> {
+        if value.category_chain().leaf().map(String::as_str) != Some("2") {
+            return Err(cooplan_definition_schema_validator::error::Error::new(
+                cooplan_definition_schema_validator::error::ErrorKind::ValueDefinitionMismatch,
+                format!("value does not belong to category '{}'", "2"),
+            ));
+        }
+
+        match serde_json::from_value(serde_json::Value::Object(value.value().clone())) {
+            Ok(typed_value) => Ok(typed_value),
+            Err(error) => Err(cooplan_definition_schema_validator::error::Error::new(
+                cooplan_definition_schema_validator::error::ErrorKind::DeserializationFailure,
+                format!("failed to convert value into 'Fruit': {}", error),
+            )),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use std::env;
+use std::fs;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    definition_registry::DefinitionRegistry,
+    http_service::{load_definitions, ValidationService},
+    json_schema::JsonSchemaExporter,
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_named_attribute, build_product_definition};
+
+const VALID_VALUE: &str = "{ \"type\": \"2\", \"version\": \"1\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }";
+const INVALID_VALUE: &str = "{ \"type\": \"2\", \"version\": \"1\", \"11\": \"many\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }";
+
+fn build_definition(version: &str) -> Definition {
+    let categories = build_product_definition(
+        vec![build_named_attribute("11", "count", "integer", false)],
+        vec![],
+    )
+    .categories();
+
+    Definition::new(version.to_string(), categories)
+}
+
+fn build_service() -> ValidationService {
+    let mut definition_registry = DefinitionRegistry::default();
+    definition_registry.register_definition(build_definition("1"));
+
+    ValidationService::new(
+        definition_registry,
+        SchemaValidator::default(),
+        JsonSchemaExporter::default(),
+    )
+}
+
+#[test]
+fn valid_value_is_answered_with_its_category_chain() {
+    let response = build_service().handle("POST", "/validate", VALID_VALUE);
+
+    assert_eq!(200, response.status);
+    assert_eq!(true, response.body["valid"]);
+    assert_eq!("Product", response.body["definition_type"]);
+    assert_eq!(
+        serde_json::json!(["1", "2"]),
+        response.body["category_chain"]
+    );
+}
+
+#[test]
+fn invalid_value_is_answered_with_structured_error() {
+    let mut validation_service = build_service();
+
+    let response = validation_service.handle("POST", "/validate", INVALID_VALUE);
+    assert_eq!(422, response.status);
+    assert_eq!("InvalidValue", response.body["error"]["kind"]);
+    assert_eq!("11", response.body["error"]["attribute_id"]);
+
+    let response = validation_service.handle("POST", "/validate", "{ \"type\": ");
+    assert_eq!(400, response.status);
+    assert_eq!("DeserializationFailure", response.body["error"]["kind"]);
+
+    let response = validation_service.handle(
+        "POST",
+        "/validate",
+        "{ \"type\": \"2\", \"version\": \"2\" }",
+    );
+    assert_eq!(422, response.status);
+    assert_eq!("UnknownDefinitionVersion", response.body["error"]["kind"]);
+}
+
+#[test]
+fn batch_is_answered_value_by_value() {
+    let response = build_service().handle(
+        "POST",
+        "/validate/batch",
+        &format!("[{}, {}, 7]", VALID_VALUE, INVALID_VALUE),
+    );
+
+    assert_eq!(200, response.status);
+    assert_eq!(1, response.body["valid"]);
+    assert_eq!(2, response.body["invalid"]);
+    assert_eq!(true, response.body["results"][0]["valid"]);
+    assert_eq!("InvalidValue", response.body["results"][1]["error"]["kind"]);
+    assert_eq!(
+        "DeserializationFailure",
+        response.body["results"][2]["error"]["kind"]
+    );
+
+    let response = build_service().handle("POST", "/validate/batch", VALID_VALUE);
+    assert_eq!(400, response.status);
+}
+
+#[test]
+fn category_schema_is_exported() {
+    let mut validation_service = build_service();
+
+    let response =
+        validation_service.handle("GET", "/definitions/1/categories/2/schema?pretty", "");
+    assert_eq!(200, response.status);
+    assert_eq!(
+        serde_json::json!({ "const": "2" }),
+        response.body["properties"]["type"]
+    );
+
+    let response = validation_service.handle("GET", "/definitions/7/categories/2/schema", "");
+    assert_eq!(404, response.status);
+    assert_eq!("UnknownDefinitionVersion", response.body["error"]["kind"]);
+
+    let response = validation_service.handle("GET", "/definitions/1/categories/9/schema", "");
+    assert_eq!(404, response.status);
+    assert_eq!("InvalidValue", response.body["error"]["kind"]);
+}
+
+#[test]
+fn unknown_routes_are_rejected() {
+    let mut validation_service = build_service();
+
+    assert_eq!(
+        405,
+        validation_service.handle("GET", "/validate", "").status
+    );
+    assert_eq!(
+        404,
+        validation_service.handle("GET", "/definitions", "").status
+    );
+}
+
+#[test]
+fn definitions_are_loaded_from_directory() {
+    let directory = env::temp_dir().join(format!(
+        "cooplan-definition-schema-validator-definitions-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&directory).expect("failed to create test directory");
+
+    for (version, extension) in [("1", "json"), ("2", "JSON")] {
+        fs::write(
+            directory.join(format!("definition-{}.{}", version, extension)),
+            serde_json::to_string(&build_definition(version)).expect("failed to serialize"),
+        )
+        .expect("failed to write definition");
+    }
+    fs::write(directory.join("README.md"), "not a definition").expect("failed to write file");
+    #[cfg(not(feature = "yaml"))]
+    fs::write(directory.join("definition-3.yaml"), "version: \"3\"").expect("failed to write file");
+
+    let definition_registry =
+        load_definitions(&directory.to_string_lossy()).expect("failed to load definitions");
+
+    assert_eq!(2, definition_registry.definitions().count());
+    assert!(definition_registry.get("2").is_some());
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn requests_are_served_on_localhost() {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::Arc;
+    use std::thread;
+
+    use serde_json::Value;
+
+    use cooplan_definition_schema_validator::http_service::serve;
+
+    let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").expect("failed to listen"));
+    let address = server
+        .server_addr()
+        .to_ip()
+        .expect("server is not listening on IP");
+
+    let serving_server = Arc::clone(&server);
+    let serving = thread::spawn(move || serve(&serving_server, &mut build_service()));
+
+    let mut stream = TcpStream::connect(address).expect("failed to connect");
+    write!(
+        stream,
+        "POST /validate HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        INVALID_VALUE.len(),
+        INVALID_VALUE
+    )
+    .expect("failed to send request");
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("failed to read response");
+
+    server.unblock();
+    serving.join().expect("server thread panicked");
+
+    let (head, body) = response.split_once("\r\n\r\n").expect("malformed response");
+    let body: Value = serde_json::from_str(body).expect("body is not JSON");
+
+    assert!(head.starts_with("HTTP/1.1 422"));
+    assert!(head.contains("application/json"));
+    assert_eq!("InvalidValue", body["error"]["kind"]);
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn oversized_request_bodies_are_rejected() {
+    use std::io::{Read, Write};
+    use std::net::{Shutdown, TcpStream};
+    use std::sync::Arc;
+    use std::thread;
+
+    use cooplan_definition_schema_validator::http_service::{serve, MAX_BODY_SIZE};
+
+    let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").expect("failed to listen"));
+    let address = server
+        .server_addr()
+        .to_ip()
+        .expect("server is not listening on IP");
+
+    let serving_server = Arc::clone(&server);
+    let serving = thread::spawn(move || serve(&serving_server, &mut build_service()));
+
+    let mut stream = TcpStream::connect(address).expect("failed to connect");
+    write!(
+        stream,
+        "POST /validate HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        MAX_BODY_SIZE + 1
+    )
+    .expect("failed to send request");
+    // The unread body is drained before closing, up to the end of the stream.
+    stream
+        .shutdown(Shutdown::Write)
+        .expect("failed to close request");
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("failed to read response");
+
+    server.unblock();
+    serving.join().expect("server thread panicked");
+
+    assert!(response.starts_with("HTTP/1.1 413"));
+}
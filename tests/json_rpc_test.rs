@@ -0,0 +1,220 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::{json, Value};
+
+use cooplan_definition_schema_validator::{
+    cli::{run, CliOptions, EXIT_SUCCESS},
+    definition_registry::DefinitionRegistry,
+    json_rpc::{JsonRpcService, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR, SERVER_ERROR},
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_named_attribute, build_named_category, build_product_definition};
+
+fn build_definition(version: &str) -> Definition {
+    let mut categories = build_product_definition(
+        vec![build_named_attribute("11", "count", "integer", false)],
+        vec![],
+    )
+    .categories();
+    categories.push(build_named_category(
+        "3",
+        "vegetable",
+        Some("1"),
+        true,
+        vec![build_named_attribute("12", "price", "price", false)],
+    ));
+
+    Definition::new(version.to_string(), categories)
+}
+
+fn build_service() -> JsonRpcService {
+    let mut definition_registry = DefinitionRegistry::default();
+    definition_registry.register_definition(build_definition("1"));
+
+    JsonRpcService::new(definition_registry, SchemaValidator::default())
+}
+
+fn call(json_rpc_service: &mut JsonRpcService, method: &str, params: Value) -> Value {
+    json_rpc_service
+        .handle_message(
+            &json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string(),
+        )
+        .expect("request was not answered")
+}
+
+#[test]
+fn values_are_validated_against_loaded_definitions() {
+    let mut json_rpc_service = build_service();
+
+    let response = call(
+        &mut json_rpc_service,
+        "validate",
+        json!({ "value": { "type": "2", "version": "1", "11": 600, "4ed908eb-50b6-4faa-9baa-a7a897cec30f": true } }),
+    );
+    assert_eq!(1, response["id"]);
+    assert_eq!(true, response["result"]["valid"]);
+
+    let response = call(
+        &mut json_rpc_service,
+        "validate",
+        json!({ "value": { "type": "2", "version": "1", "11": "many", "4ed908eb-50b6-4faa-9baa-a7a897cec30f": true } }),
+    );
+    assert_eq!(false, response["result"]["valid"]);
+    assert_eq!("InvalidValue", response["result"]["error"]["kind"]);
+    assert_eq!("11", response["result"]["error"]["attribute_id"]);
+}
+
+#[test]
+fn definitions_are_loaded_replaced_and_removed() {
+    let mut json_rpc_service = build_service();
+
+    let response = call(
+        &mut json_rpc_service,
+        "definitions/load",
+        json!({ "definition": build_definition("2") }),
+    );
+    assert_eq!(
+        json!({ "version": "2", "replaced": false }),
+        response["result"]
+    );
+
+    let response = call(
+        &mut json_rpc_service,
+        "definitions/load",
+        json!({ "definition": build_definition("1") }),
+    );
+    assert_eq!(true, response["result"]["replaced"]);
+
+    let response = call(&mut json_rpc_service, "definitions/list", Value::Null);
+    assert_eq!(json!(["1", "2"]), response["result"]["versions"]);
+
+    let response = call(
+        &mut json_rpc_service,
+        "definitions/remove",
+        json!({ "version": "1" }),
+    );
+    assert_eq!(true, response["result"]["removed"]);
+
+    let response = call(&mut json_rpc_service, "definitions/list", json!({}));
+    assert_eq!(json!(["2"]), response["result"]["versions"]);
+}
+
+#[test]
+fn definitions_are_linted() {
+    let mut json_rpc_service = build_service();
+
+    let response = call(&mut json_rpc_service, "lint", json!({ "version": "1" }));
+    assert_eq!(true, response["result"]["has_errors"]);
+    assert_eq!(
+        "ValidationNotRegistered",
+        response["result"]["issues"][0]["kind"]
+    );
+    assert_eq!("12", response["result"]["issues"][0]["attribute_id"]);
+
+    let response = call(
+        &mut json_rpc_service,
+        "lint",
+        json!({ "definition": build_definition("2") }),
+    );
+    assert_eq!(true, response["result"]["has_errors"]);
+}
+
+#[test]
+fn category_attributes_are_listed() {
+    let mut json_rpc_service = build_service();
+
+    let response = call(
+        &mut json_rpc_service,
+        "attributes",
+        json!({ "version": "1", "category_id": "3" }),
+    );
+    let attribute_ids: Vec<&Value> = response["result"]["attributes"]
+        .as_array()
+        .expect("attributes are not an array")
+        .iter()
+        .map(|attribute| &attribute["id"])
+        .collect();
+    assert_eq!(
+        vec!["12", "11", "4ed908eb-50b6-4faa-9baa-a7a897cec30f"],
+        attribute_ids
+    );
+
+    let response = call(
+        &mut json_rpc_service,
+        "attributes",
+        json!({ "version": "7", "category_id": "3" }),
+    );
+    assert_eq!(SERVER_ERROR, response["error"]["code"]);
+    assert_eq!(
+        "UnknownDefinitionVersion",
+        response["error"]["data"]["kind"]
+    );
+}
+
+#[test]
+fn protocol_errors_are_reported() {
+    let mut json_rpc_service = build_service();
+
+    let response = json_rpc_service
+        .handle_message("{ \"jsonrpc\": ")
+        .expect("parse error was not answered");
+    assert_eq!(PARSE_ERROR, response["error"]["code"]);
+
+    let response = call(&mut json_rpc_service, "format", json!({}));
+    assert_eq!(METHOD_NOT_FOUND, response["error"]["code"]);
+
+    let response = call(&mut json_rpc_service, "validate", json!({ "value": 7 }));
+    assert_eq!(INVALID_PARAMS, response["error"]["code"]);
+
+    assert!(json_rpc_service
+        .handle_message(&json!({ "jsonrpc": "2.0", "method": "definitions/list" }).to_string())
+        .is_none());
+
+    let responses = json_rpc_service
+        .handle_message(
+            &json!([
+                { "jsonrpc": "2.0", "id": 1, "method": "definitions/list" },
+                { "jsonrpc": "2.0", "method": "definitions/list" },
+                { "jsonrpc": "2.0", "id": 2, "method": "format" },
+            ])
+            .to_string(),
+        )
+        .expect("batch was not answered");
+    assert_eq!(2, responses.as_array().unwrap().len());
+}
+
+#[test]
+fn cli_serves_until_shutdown() {
+    let options =
+        CliOptions::parse(vec!["--json-rpc".to_string()]).expect("failed to parse arguments");
+    let requests = format!(
+        "{}\n\n{}\n{}\n",
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "definitions/load", "params": { "definition": build_definition("1") } }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "definitions/list" }),
+    );
+
+    let mut output: Vec<u8> = Vec::new();
+    let mut error_output: Vec<u8> = Vec::new();
+
+    let exit_code = run(
+        &options,
+        &mut SchemaValidator::default(),
+        &mut requests.as_bytes(),
+        &mut output,
+        &mut error_output,
+    );
+
+    let responses: Vec<Value> = String::from_utf8(output)
+        .expect("output is not UTF-8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response is not JSON"))
+        .collect();
+
+    assert_eq!(EXIT_SUCCESS, exit_code);
+    assert_eq!(2, responses.len());
+    assert_eq!("1", responses[0]["result"]["version"]);
+    assert_eq!(Value::Null, responses[1]["result"]);
+}
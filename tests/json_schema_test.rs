@@ -0,0 +1,269 @@
+mod common;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+
+use cooplan_definition_schema_validator::{
+    error::ErrorKind,
+    json_schema::{JsonSchemaExporter, JsonSchemaImporter},
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_named_attribute, build_product_definition};
+use serde_json::json;
+
+fn build_definition() -> Definition {
+    let price_per_kg_attribute = ValidatedSourceAttribute {
+        unit: Some("EUR/kg".to_string()),
+        ..build_named_attribute("12", "price_per_kg", "decimal", false)
+    };
+
+    build_product_definition(
+        vec![
+            build_named_attribute("10", "product_name", "string", false),
+            build_named_attribute("11", "count", "integer", false),
+        ],
+        vec![
+            price_per_kg_attribute,
+            build_named_attribute("13", "type", "string", true),
+        ],
+    )
+}
+
+#[test]
+fn exports_category_with_inherited_attributes() {
+    let schema = JsonSchemaExporter::default()
+        .export_category(&build_definition(), &"2".to_string())
+        .expect("failed to export category");
+
+    assert_eq!(
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "fruit",
+            "type": "object",
+            "properties": {
+                "version": { "const": "1" },
+                "type": { "const": "2" },
+                "10": { "type": "string", "title": "product_name" },
+                "11": { "type": "integer", "title": "count" },
+                "12": { "type": "number", "title": "price_per_kg", "x-unit": "EUR/kg" },
+                "13": { "type": "string", "title": "type" },
+                "4ed908eb-50b6-4faa-9baa-a7a897cec30f": { "type": "boolean", "title": "IS_PRODUCT" }
+            },
+            "required": ["version", "type", "12", "10", "11", "4ed908eb-50b6-4faa-9baa-a7a897cec30f"]
+        }),
+        schema
+    );
+}
+
+#[test]
+fn exports_registered_data_type_schemas() {
+    let mut definition = build_definition();
+    let mut categories = definition.categories();
+    categories[0].attributes[0].data_type = "email".to_string();
+    definition = Definition::new(definition.version(), categories);
+
+    assert_eq!(
+        ErrorKind::DataTypeNotRegistered,
+        JsonSchemaExporter::default()
+            .export_category(&definition, &"2".to_string())
+            .unwrap_err()
+            .kind()
+    );
+
+    let mut exporter = JsonSchemaExporter::default();
+    exporter.register_schema(
+        "email".to_string(),
+        json!({ "type": "string", "format": "email" }),
+    );
+
+    let schema = exporter
+        .export_category(&definition, &"2".to_string())
+        .expect("failed to export category");
+
+    assert_eq!(
+        json!({ "type": "string", "format": "email", "title": "product_name" }),
+        schema["properties"]["10"]
+    );
+}
+
+#[test]
+fn unknown_category_is_reported() {
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        JsonSchemaExporter::default()
+            .export_category(&build_definition(), &"3".to_string())
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn imports_exported_category() {
+    let schema = JsonSchemaExporter::default()
+        .export_category(&build_definition(), &"2".to_string())
+        .expect("failed to export category");
+
+    let definition = JsonSchemaImporter::default()
+        .import("1".to_string(), &schema)
+        .expect("failed to import schema");
+
+    let categories = definition.categories();
+    assert_eq!(1, categories.len());
+    assert_eq!("2", categories[0].id);
+    assert_eq!("fruit", categories[0].name);
+    assert!(categories[0].selectable_as_last);
+
+    let price_per_kg_attribute = categories[0]
+        .attributes
+        .iter()
+        .find(|attribute| attribute.id == "12")
+        .expect("failed to find price per kg attribute");
+    assert_eq!("price_per_kg", price_per_kg_attribute.name);
+    assert_eq!("decimal", price_per_kg_attribute.data_type);
+    assert_eq!(Some("EUR/kg".to_string()), price_per_kg_attribute.unit);
+    assert!(!price_per_kg_attribute.optional);
+
+    let variety_attribute = categories[0]
+        .attributes
+        .iter()
+        .find(|attribute| attribute.id == "13")
+        .expect("failed to find variety attribute");
+    assert!(variety_attribute.optional);
+}
+
+#[test]
+fn imports_inheritance_from_definitions() {
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$defs": {
+            "product": {
+                "title": "product",
+                "x-category-id": "1",
+                "type": "object",
+                "properties": {
+                    "10": { "type": "string", "title": "product_name" },
+                    "11": { "type": "integer", "title": "count" },
+                    "4ed908eb-50b6-4faa-9baa-a7a897cec30f": { "type": "boolean", "title": "IS_PRODUCT" }
+                },
+                "required": ["10", "11", "4ed908eb-50b6-4faa-9baa-a7a897cec30f"]
+            },
+            "fruit": {
+                "title": "fruit",
+                "x-category-id": "2",
+                "allOf": [
+                    { "$ref": "#/$defs/product" },
+                    {
+                        "properties": { "12": { "type": "number", "title": "price_per_kg" } },
+                        "required": ["12"]
+                    }
+                ]
+            }
+        }
+    });
+
+    let definition = JsonSchemaImporter::default()
+        .import("1".to_string(), &schema)
+        .expect("failed to import schema");
+
+    let categories = definition.categories();
+    let product_category = categories
+        .iter()
+        .find(|category| category.id == "1")
+        .expect("failed to find product category");
+    let fruit_category = categories
+        .iter()
+        .find(|category| category.id == "2")
+        .expect("failed to find fruit category");
+
+    assert_eq!(None, product_category.parent);
+    assert!(!product_category.selectable_as_last);
+    assert_eq!(Some("1".to_string()), fruit_category.parent);
+    assert_eq!(Some("product".to_string()), fruit_category.parent_name);
+    assert!(fruit_category.selectable_as_last);
+
+    let json_value_string: String = String::from(
+        "{ \"type\": \"2\", \"version\": \"1\", \"10\": \"Pear\", \"11\": 600, \"12\": 15.39, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    assert!(SchemaValidator::default()
+        .validate(json_value_string, definition)
+        .is_ok());
+}
+
+#[test]
+fn unsupported_constructs_are_reported() {
+    let schema = json!({
+        "$defs": {
+            "product": {
+                "x-category-id": "1",
+                "properties": {
+                    "10": { "type": "string", "title": "product_name", "maxLength": 20 },
+                    "11": { "type": "array" }
+                },
+                "oneOf": []
+            },
+            "fruit": {
+                "x-category-id": "2",
+                "allOf": [{ "$ref": "https://example.com/product.json" }]
+            }
+        }
+    });
+
+    let error = JsonSchemaImporter::default()
+        .import("1".to_string(), &schema)
+        .unwrap_err();
+
+    assert_eq!(ErrorKind::UnsupportedSchema, error.kind());
+    assert!(error
+        .message
+        .contains("at '/$defs/product/properties/10/maxLength'"));
+    assert!(error.message.contains("at '/$defs/product/properties/11'"));
+    assert!(error.message.contains("at '/$defs/product/oneOf'"));
+    assert!(error.message.contains("at '/$defs/fruit/allOf/0'"));
+}
+
+#[test]
+fn cyclic_inheritance_is_reported() {
+    let schema = json!({
+        "$defs": {
+            "product": { "allOf": [{ "$ref": "#/$defs/fruit" }] },
+            "fruit": { "allOf": [{ "$ref": "#/$defs/product" }] }
+        }
+    });
+
+    let error = JsonSchemaImporter::default()
+        .import("1".to_string(), &schema)
+        .unwrap_err();
+
+    assert_eq!(ErrorKind::UnsupportedSchema, error.kind());
+    assert!(error.message.contains("cyclic inheritance"));
+}
+
+#[test]
+fn imports_registered_data_type_schemas() {
+    let schema = json!({
+        "x-category-id": "1",
+        "title": "contact",
+        "properties": {
+            "20": { "type": "string", "format": "email", "title": "email" },
+            "21": { "type": "string", "title": "name" }
+        }
+    });
+
+    let mut importer = JsonSchemaImporter::default();
+    importer.register_schema(
+        "email".to_string(),
+        json!({ "type": "string", "format": "email" }),
+    );
+
+    let definition = importer
+        .import("1".to_string(), &schema)
+        .expect("failed to import schema");
+    let attributes = &definition.categories()[0].attributes;
+
+    assert_eq!("email", attributes[0].data_type);
+    assert_eq!("string", attributes[1].data_type);
+    assert!(attributes[0].optional);
+}
@@ -0,0 +1,256 @@
+mod common;
+
+use cooplan_definitions_lib::{
+    definition::Definition, validated_source_attribute::ValidatedSourceAttribute,
+};
+use serde_json::{json, Value};
+
+use cooplan_definition_schema_validator::{
+    json_rpc::{INVALID_REQUEST, METHOD_NOT_FOUND},
+    language_server::{LanguageServer, MAX_MESSAGE_SIZE},
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_named_attribute, build_named_category, build_product_definition};
+
+const URI: &str = "file:///products/apple.json";
+
+fn build_definition() -> Definition {
+    let weight_attribute = ValidatedSourceAttribute {
+        unit: Some("kg".to_string()),
+        ..build_named_attribute("12", "weight", "decimal", true)
+    };
+
+    let mut categories = build_product_definition(
+        vec![build_named_attribute("11", "count", "integer", false)],
+        vec![weight_attribute],
+    )
+    .categories();
+    categories.push(build_named_category("3", "tool", None, true, vec![]));
+
+    Definition::new("1".to_string(), categories)
+}
+
+fn open(language_server: &mut LanguageServer, text: &str) -> Vec<Value> {
+    language_server.handle_message(
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": URI, "languageId": "json", "version": 1, "text": text },
+            },
+        })
+        .to_string(),
+    )
+}
+
+fn request(language_server: &mut LanguageServer, method: &str, line: u64, character: u64) -> Value {
+    let mut responses = language_server.handle_message(
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": method,
+            "params": {
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+            },
+        })
+        .to_string(),
+    );
+
+    assert_eq!(1, responses.len());
+
+    responses.remove(0)["result"].take()
+}
+
+fn labels(items: &Value) -> Vec<&str> {
+    items
+        .as_array()
+        .expect("completion items are not an array")
+        .iter()
+        .map(|item| item["label"].as_str().expect("label is not a string"))
+        .collect()
+}
+
+#[test]
+fn diagnostics_are_published_for_opened_and_changed_documents() {
+    let mut language_server = LanguageServer::new(build_definition(), SchemaValidator::default());
+
+    let notifications = open(
+        &mut language_server,
+        "{\n  \"type\": \"2\",\n  \"version\": \"1\",\n  \"11\": \"many\",\n  \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true\n}",
+    );
+
+    assert_eq!(1, notifications.len());
+    assert_eq!(
+        "textDocument/publishDiagnostics",
+        notifications[0]["method"]
+    );
+
+    let diagnostic = &notifications[0]["params"]["diagnostics"][0];
+    assert_eq!("InvalidValue", diagnostic["code"]);
+    assert_eq!(
+        json!({ "start": { "line": 3, "character": 8 }, "end": { "line": 3, "character": 14 } }),
+        diagnostic["range"]
+    );
+
+    let notifications = language_server.handle_message(
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": URI, "version": 2 },
+                "contentChanges": [{
+                    "text": "{ \"type\": \"2\", \"version\": \"1\", \"11\": 3, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+                }],
+            },
+        })
+        .to_string(),
+    );
+
+    assert_eq!(json!([]), notifications[0]["params"]["diagnostics"]);
+}
+
+#[test]
+fn attribute_ids_are_completed_for_the_document_category() {
+    let mut language_server = LanguageServer::new(build_definition(), SchemaValidator::default());
+
+    open(&mut language_server, "{\n  \"type\": \"2\",\n  \"\n}");
+
+    let items = request(&mut language_server, "textDocument/completion", 2, 3);
+    assert_eq!(
+        vec!["12", "11", "4ed908eb-50b6-4faa-9baa-a7a897cec30f"],
+        labels(&items)
+    );
+    assert_eq!("weight", items[0]["detail"]);
+    assert_eq!("12", items[0]["insertText"]);
+
+    open(&mut language_server, "{\n  \"type\": \"3\",\n  \n}");
+
+    let items = request(&mut language_server, "textDocument/completion", 2, 2);
+    assert_eq!(Vec::<&str>::new(), labels(&items));
+
+    open(&mut language_server, "{\n  \n}");
+
+    let items = request(&mut language_server, "textDocument/completion", 1, 2);
+    assert_eq!(
+        vec!["11", "4ed908eb-50b6-4faa-9baa-a7a897cec30f", "12"],
+        labels(&items)
+    );
+    assert_eq!("\"11\"", items[0]["insertText"]);
+}
+
+#[test]
+fn type_is_completed_with_categories_selectable_as_last() {
+    let mut language_server = LanguageServer::new(build_definition(), SchemaValidator::default());
+
+    open(&mut language_server, "{ \"version\": \"\", \"type\": \"");
+
+    let items = request(&mut language_server, "textDocument/completion", 0, 26);
+    assert_eq!(vec!["2", "3"], labels(&items));
+    assert_eq!("fruit", items[0]["detail"]);
+
+    let items = request(&mut language_server, "textDocument/completion", 0, 14);
+    assert_eq!(vec!["1"], labels(&items));
+}
+
+#[test]
+fn hover_shows_attribute_data_type_and_unit() {
+    let mut language_server = LanguageServer::new(build_definition(), SchemaValidator::default());
+
+    open(
+        &mut language_server,
+        "{\n  \"type\": \"2\",\n  \"12\": 0.2\n}",
+    );
+
+    let hover = request(&mut language_server, "textDocument/hover", 2, 3);
+    assert_eq!(
+        "**weight**: `decimal` (`12`) in kg, optional",
+        hover["contents"]["value"]
+    );
+    assert_eq!(
+        json!({ "start": { "line": 2, "character": 2 }, "end": { "line": 2, "character": 6 } }),
+        hover["range"]
+    );
+
+    let hover = request(&mut language_server, "textDocument/hover", 1, 11);
+    assert_eq!(
+        "**fruit**: category `product > fruit`",
+        hover["contents"]["value"]
+    );
+
+    assert_eq!(
+        Value::Null,
+        request(&mut language_server, "textDocument/hover", 2, 9)
+    );
+}
+
+#[test]
+fn messages_are_served_until_exit() {
+    let mut language_server = LanguageServer::new(build_definition(), SchemaValidator::default());
+
+    let mut input = String::new();
+
+    for message in [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/formatting", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+        json!({ "jsonrpc": "2.0", "id": 4, "method": "initialize", "params": {} }),
+    ] {
+        let body = message.to_string();
+        input.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+    }
+
+    let mut output: Vec<u8> = Vec::new();
+
+    language_server
+        .serve(&mut input.as_bytes(), &mut output)
+        .expect("failed to serve");
+
+    let output = String::from_utf8(output).expect("output is not UTF-8");
+    let responses: Vec<Value> = output
+        .split("Content-Length: ")
+        .skip(1)
+        .map(|message| {
+            let (length, body) = message.split_once("\r\n\r\n").expect("malformed message");
+            assert_eq!(length.parse::<usize>().unwrap(), body.len());
+
+            serde_json::from_str(body).expect("body is not JSON")
+        })
+        .collect();
+
+    assert!(language_server.is_shutdown_requested());
+    assert_eq!(3, responses.len());
+    assert_eq!(
+        true,
+        responses[0]["result"]["capabilities"]["hoverProvider"]
+    );
+    assert_eq!(METHOD_NOT_FOUND, responses[1]["error"]["code"]);
+    assert_eq!(3, responses[2]["id"]);
+}
+
+#[test]
+fn oversized_messages_are_skipped_and_answered_with_an_error() {
+    let mut language_server = LanguageServer::new(build_definition(), SchemaValidator::default());
+
+    let mut input = format!(
+        "Content-Length: {}\r\n\r\n{}",
+        MAX_MESSAGE_SIZE + 1,
+        " ".repeat(MAX_MESSAGE_SIZE + 1)
+    );
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" }).to_string();
+    input.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+
+    let mut output: Vec<u8> = Vec::new();
+
+    language_server
+        .serve(&mut input.as_bytes(), &mut output)
+        .expect("failed to serve");
+
+    let output = String::from_utf8(output).expect("output is not UTF-8");
+
+    assert!(language_server.is_shutdown_requested());
+    assert!(output.contains(&format!("\"code\":{}", INVALID_REQUEST)));
+}
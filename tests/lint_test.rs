@@ -0,0 +1,158 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    error::ErrorKind, lint::Severity, schema_validator::SchemaValidator,
+    validations::validate_string,
+};
+
+use common::{build_attribute, build_category};
+
+fn build_definition() -> Definition {
+    Definition::new(
+        "1".to_string(),
+        vec![
+            build_category(
+                "1",
+                None,
+                false,
+                vec![build_attribute("10", "string", false)],
+            ),
+            build_category(
+                "2",
+                Some("1"),
+                true,
+                vec![build_attribute("11", "integer", false)],
+            ),
+        ],
+    )
+}
+
+#[test]
+fn valid_definition_has_no_issues() {
+    let report = SchemaValidator::default().lint(&build_definition());
+
+    assert!(report.issues.is_empty());
+    assert!(!report.has_errors());
+}
+
+#[test]
+fn unregistered_data_type_is_reported() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![build_category(
+            "1",
+            None,
+            true,
+            vec![build_attribute("10", "color", false)],
+        )],
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+    let report = schema_validator.lint(&definition);
+
+    assert!(report.has_errors());
+    assert_eq!(ErrorKind::ValidationNotRegistered, report.issues[0].kind);
+    assert_eq!(Some("10".to_string()), report.issues[0].attribute_id);
+
+    schema_validator.register_validation("color".to_string(), Box::new(validate_string));
+
+    assert!(!schema_validator.lint(&definition).has_errors());
+}
+
+#[test]
+fn reserved_attribute_ids_are_reported() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![build_category(
+            "1",
+            None,
+            true,
+            vec![
+                build_attribute("version", "string", false),
+                build_attribute("type", "string", false),
+            ],
+        )],
+    );
+
+    let report = SchemaValidator::default().lint(&definition);
+
+    assert_eq!(2, report.errors().count());
+    assert!(report
+        .issues
+        .iter()
+        .all(|issue| issue.kind == ErrorKind::InvalidDefinition));
+}
+
+#[test]
+fn duplicate_attribute_is_reported_once() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![
+            build_category(
+                "1",
+                None,
+                false,
+                vec![
+                    build_attribute("10", "string", false),
+                    build_attribute("10", "string", false),
+                ],
+            ),
+            build_category("2", Some("1"), true, vec![]),
+            build_category("3", Some("1"), true, vec![]),
+        ],
+    );
+
+    let report = SchemaValidator::default().lint(&definition);
+
+    assert_eq!(1, report.issues.len());
+    assert_eq!(ErrorKind::InvalidDefinition, report.issues[0].kind);
+    assert_eq!(Some("1".to_string()), report.issues[0].category_id);
+}
+
+#[test]
+fn unreachable_categories_are_reported() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![
+            build_category("1", None, true, vec![]),
+            build_category("2", Some("7"), true, vec![]),
+            build_category("3", Some("4"), true, vec![]),
+            build_category("4", Some("3"), true, vec![]),
+        ],
+    );
+
+    let report = SchemaValidator::default().lint(&definition);
+    let category_ids: Vec<Option<String>> = report
+        .errors()
+        .map(|issue| issue.category_id.clone())
+        .collect();
+
+    assert_eq!(
+        vec![
+            Some("2".to_string()),
+            Some("3".to_string()),
+            Some("4".to_string())
+        ],
+        category_ids
+    );
+}
+
+#[test]
+fn unusable_leaf_category_is_a_warning() {
+    let definition = Definition::new(
+        "1".to_string(),
+        vec![
+            build_category("1", None, false, vec![]),
+            build_category("2", Some("1"), false, vec![]),
+        ],
+    );
+
+    let report = SchemaValidator::default().lint(&definition);
+
+    assert!(!report.has_errors());
+    assert_eq!(1, report.warnings().count());
+    assert_eq!(Severity::Warning, report.issues[0].severity);
+    assert_eq!(Some("2".to_string()), report.issues[0].category_id);
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::Value;
+
+use cooplan_definition_schema_validator::{
+    error::ErrorKind,
+    migration::{Migration, MigrationStep, Migrator},
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_category, build_is_product_attribute, build_named_attribute};
+
+fn build_target_definition() -> Definition {
+    let product_category = build_category(
+        "1",
+        None,
+        false,
+        vec![
+            build_named_attribute("20", "product_name", "string", false),
+            build_named_attribute("11", "count", "integer", false),
+            build_named_attribute("14", "origin", "string", false),
+            build_is_product_attribute(),
+        ],
+    );
+    let produce_category = build_category("4", Some("1"), true, vec![]);
+
+    Definition::new("3".to_string(), vec![product_category, produce_category])
+}
+
+fn build_migrator() -> Migrator {
+    let mut migrator = Migrator::default();
+
+    migrator.register_migration(Migration {
+        from_version: "2".to_string(),
+        to_version: "3".to_string(),
+        steps: vec![
+            MigrationStep::SetDefault {
+                attribute_id: "14".to_string(),
+                value: Value::String("unknown".to_string()),
+            },
+            MigrationStep::DropAttribute {
+                attribute_id: "15".to_string(),
+            },
+            MigrationStep::ReparentCategory {
+                from: "3".to_string(),
+                to: "4".to_string(),
+            },
+        ],
+    });
+
+    migrator.register_migration(Migration {
+        from_version: "1".to_string(),
+        to_version: "2".to_string(),
+        steps: vec![
+            MigrationStep::RenameAttribute {
+                from: "10".to_string(),
+                to: "20".to_string(),
+            },
+            MigrationStep::ConvertType {
+                attribute_id: "11".to_string(),
+                data_type: "integer".to_string(),
+            },
+        ],
+    });
+
+    migrator
+}
+
+#[test]
+fn migrates_value_across_several_versions() {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"3\", \"version\": \"1\", \"10\": \"Carrot\", \"11\": \"600\", \"15\": \"legacy\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+
+    let (definition_value, report) = build_migrator()
+        .migrate(
+            json_value_string,
+            &mut schema_validator,
+            build_target_definition(),
+        )
+        .expect("failed to migrate value");
+
+    assert_eq!("3", definition_value.definition());
+    assert_eq!("Carrot", definition_value.get_str("20").unwrap());
+    assert_eq!(600, definition_value.get_i64("11").unwrap());
+    assert_eq!("unknown", definition_value.get_str("14").unwrap());
+    assert_eq!(
+        Some(&"4".to_string()),
+        definition_value.category_chain().leaf()
+    );
+
+    assert_eq!(vec!["1", "2", "3"], report.versions);
+    assert_eq!(5, report.changes.len());
+    assert_eq!(
+        MigrationStep::RenameAttribute {
+            from: "10".to_string(),
+            to: "20".to_string(),
+        },
+        report.changes[0].step
+    );
+    assert_eq!("2", report.changes[4].from_version);
+    assert_eq!("3", report.changes[4].to_version);
+}
+
+#[test]
+fn missing_migration_is_reported() {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"4\", \"version\": \"0\", \"20\": \"Carrot\", \"11\": 600, \"14\": \"Spain\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::MigrationNotFound,
+        build_migrator()
+            .migrate(
+                json_value_string,
+                &mut schema_validator,
+                build_target_definition(),
+            )
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn failed_conversion_is_reported() {
+    for count in ["\"many\"", "1e30", "-1e19", "9300000000000000000"] {
+        let json_value_string: String = format!(
+            "{{ \"type\": \"3\", \"version\": \"1\", \"10\": \"Carrot\", \"11\": {}, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }}",
+            count
+        );
+
+        let mut schema_validator = SchemaValidator::default();
+
+        assert_eq!(
+            ErrorKind::MigrationFailure,
+            build_migrator()
+                .migrate(
+                    json_value_string,
+                    &mut schema_validator,
+                    build_target_definition(),
+                )
+                .unwrap_err()
+                .kind(),
+            "count {} was converted",
+            count
+        );
+    }
+}
+
+#[test]
+fn migrated_value_is_validated_against_target_definition() {
+    let json_value_string: String = String::from(
+        "{ \"type\": \"3\", \"version\": \"2\", \"20\": \"Carrot\", \"11\": 6.5, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        build_migrator()
+            .migrate(
+                json_value_string,
+                &mut schema_validator,
+                build_target_definition(),
+            )
+            .unwrap_err()
+            .kind()
+    );
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::{Map, Value};
+
+use cooplan_definition_schema_validator::{
+    error::ErrorKind,
+    mutation::{MutationKind, ValueMutator},
+    sample::{SampleGenerator, SampleRng},
+    schema_validator::SchemaValidator,
+    version_policy::VersionPolicy,
+};
+
+use common::{build_category, build_named_attribute, IS_PRODUCT_ID, IS_SERVICE_ID};
+
+fn build_definition() -> Definition {
+    let product_category = build_category(
+        "1",
+        None,
+        true,
+        vec![
+            build_named_attribute("10", "product_name", "string", false),
+            build_named_attribute("11", "count", "integer", true),
+            build_named_attribute(IS_PRODUCT_ID, "IS_PRODUCT", "boolean", true),
+            build_named_attribute(IS_SERVICE_ID, "IS_SERVICE", "boolean", true),
+        ],
+    );
+
+    Definition::new("1.0.0".to_string(), vec![product_category])
+}
+
+fn build_value() -> Map<String, Value> {
+    serde_json::from_str(
+        "{ \"type\": \"1\", \"version\": \"1.0.0\", \"10\": \"Pear\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+    )
+    .expect("failed to parse value")
+}
+
+fn assert_mutations_fail_as_expected(
+    value_mutator: &ValueMutator,
+    schema_validator: &mut SchemaValidator,
+    value: &Map<String, Value>,
+) {
+    for mutation in value_mutator
+        .mutate(&build_definition(), value)
+        .expect("failed to mutate value")
+    {
+        match schema_validator.validate_object(mutation.value.clone(), build_definition()) {
+            Ok(_) => panic!("mutation {:?} is valid", mutation.kind),
+            Err(error) => assert_eq!(
+                mutation.expected_error,
+                error.kind(),
+                "mutation {:?} failed with '{}'",
+                mutation.kind,
+                error
+            ),
+        }
+    }
+}
+
+#[test]
+fn every_mutation_fails_with_expected_error() {
+    assert_mutations_fail_as_expected(
+        &ValueMutator::default(),
+        &mut SchemaValidator::default(),
+        &build_value(),
+    );
+}
+
+#[test]
+fn mutations_break_each_rule() {
+    let kinds: Vec<MutationKind> = ValueMutator::default()
+        .mutate(&build_definition(), &build_value())
+        .expect("failed to mutate value")
+        .into_iter()
+        .map(|mutation| mutation.kind)
+        .collect();
+
+    let product_marker = IS_PRODUCT_ID.to_string();
+
+    assert_eq!(
+        vec![
+            MutationKind::RemoveVersion,
+            MutationKind::RetypeVersion,
+            MutationKind::ChangeVersion,
+            MutationKind::RemoveType,
+            MutationKind::RetypeType,
+            MutationKind::UnknownType,
+            MutationKind::RemoveAttribute {
+                attribute_id: "10".to_string()
+            },
+            MutationKind::RetypeAttribute {
+                attribute_id: "10".to_string()
+            },
+            MutationKind::RetypeAttribute {
+                attribute_id: "11".to_string()
+            },
+            MutationKind::RetypeAttribute {
+                attribute_id: product_marker.clone()
+            },
+            MutationKind::RemoveMarker {
+                attribute_id: product_marker.clone()
+            },
+            MutationKind::FalseMarker {
+                attribute_id: product_marker
+            },
+            MutationKind::ConflictingMarker {
+                attribute_id: IS_SERVICE_ID.to_string()
+            },
+        ],
+        kinds
+    );
+}
+
+#[test]
+fn changed_version_is_rejected_by_version_policy() {
+    let mut value_mutator = ValueMutator::default();
+    value_mutator.set_version_policy(VersionPolicy::SemverCompatible);
+
+    let mut schema_validator = SchemaValidator::default();
+    schema_validator.set_version_policy(VersionPolicy::SemverCompatible);
+
+    assert_mutations_fail_as_expected(&value_mutator, &mut schema_validator, &build_value());
+}
+
+#[test]
+fn generated_samples_are_mutated() {
+    let sample_generator = SampleGenerator::default();
+    let value_mutator = ValueMutator::default();
+    let mut schema_validator = SchemaValidator::default();
+    let mut rng = SampleRng::seed_from_u64(3);
+
+    for _ in 0..20 {
+        let value = sample_generator
+            .generate(&build_definition(), &"1".to_string(), &mut rng)
+            .expect("failed to generate product");
+
+        assert_mutations_fail_as_expected(&value_mutator, &mut schema_validator, &value);
+    }
+}
+
+#[test]
+fn value_of_unknown_category_cannot_be_mutated() {
+    let mut value = build_value();
+    value.insert("type".to_string(), Value::String("2".to_string()));
+
+    assert_eq!(
+        ErrorKind::InvalidValue,
+        ValueMutator::default()
+            .mutate(&build_definition(), &value)
+            .unwrap_err()
+            .kind()
+    );
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::Value;
+
+use cooplan_definition_schema_validator::{
+    error::{Error, ErrorKind},
+    report::{to_junit_xml, to_sarif, ValidationOutcome, SARIF_VERSION, STDIN_SOURCE},
+    schema_validator::SchemaValidator,
+};
+
+use common::{build_is_product_attribute, build_named_attribute, build_named_category};
+
+fn build_definition() -> Definition {
+    let product_category = build_named_category(
+        "1",
+        "product",
+        None,
+        true,
+        vec![
+            build_named_attribute("11", "count", "integer", false),
+            build_is_product_attribute(),
+        ],
+    );
+
+    Definition::new("1".to_string(), vec![product_category])
+}
+
+fn build_outcomes() -> Vec<ValidationOutcome> {
+    let mut schema_validator = SchemaValidator::default();
+
+    vec![
+        ValidationOutcome::new(
+            "values.ndjson",
+            Some(1),
+            schema_validator.validate(
+                String::from("{ \"type\": \"1\", \"version\": \"1\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }"),
+                build_definition(),
+            ),
+        ),
+        ValidationOutcome::new(
+            "values.ndjson",
+            Some(2),
+            schema_validator.validate(
+                String::from("{ \"type\": \"1\", \"version\": \"1\", \"11\": \"<many>\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }"),
+                build_definition(),
+            ),
+        ),
+        ValidationOutcome::new::<()>(
+            "broken.json",
+            None,
+            Err(Error::new(ErrorKind::DeserializationFailure, "unexpected \"&\"")),
+        ),
+    ]
+}
+
+#[test]
+fn validation_errors_carry_the_attribute() {
+    let outcomes = build_outcomes();
+
+    assert!(outcomes[0].is_valid());
+    assert_eq!(
+        Some("11".to_string()),
+        outcomes[1]
+            .error
+            .as_ref()
+            .and_then(|error| error.attribute_id.clone())
+    );
+    assert_eq!("values.ndjson:2", outcomes[1].location());
+    assert_eq!("values.ndjson:2:38", outcomes[1].error_location());
+    assert_eq!("broken.json", outcomes[2].location());
+}
+
+#[test]
+fn junit_xml_has_one_test_case_per_outcome() {
+    let xml = to_junit_xml("catalog", &build_outcomes());
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(xml.contains("<testsuite name=\"catalog\" tests=\"3\" failures=\"2\">"));
+    assert!(xml.contains("<testcase name=\"values.ndjson:1\" classname=\"catalog\" />"));
+    assert!(xml.contains("<failure type=\"InvalidValue\""));
+    assert!(xml.contains(">attribute: 11</failure>"));
+    assert!(xml.contains("message=\"unexpected &quot;&amp;&quot;\""));
+    assert!(!xml.contains("<many>"));
+}
+
+#[test]
+fn sarif_has_one_result_per_failure() {
+    let sarif = to_sarif("cooplan-validate", &build_outcomes());
+
+    assert_eq!(SARIF_VERSION, sarif["version"]);
+
+    let run = &sarif["runs"][0];
+    assert_eq!("cooplan-validate", run["tool"]["driver"]["name"]);
+    assert_eq!(2, run["tool"]["driver"]["rules"].as_array().unwrap().len());
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(2, results.len());
+    assert_eq!("InvalidValue", results[0]["ruleId"]);
+    assert_eq!(
+        "values.ndjson",
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+    );
+    assert_eq!(
+        serde_json::json!({ "startLine": 2, "startColumn": 38, "endLine": 2, "endColumn": 46 }),
+        results[0]["locations"][0]["physicalLocation"]["region"]
+    );
+    assert_eq!(
+        "11",
+        results[0]["locations"][0]["logicalLocations"][0]["name"]
+    );
+    assert_eq!("DeserializationFailure", results[1]["ruleId"]);
+    assert!(results[1]["locations"][0]["physicalLocation"]
+        .get("region")
+        .is_none());
+}
+
+#[test]
+fn sarif_locates_sources_by_uri() {
+    let current_directory = std::env::current_dir().expect("failed to get current directory");
+
+    let uris: Vec<Value> = [
+        "./values/new batch.ndjson".to_string(),
+        "values\\a:b.json".to_string(),
+        current_directory
+            .join("values.json")
+            .to_string_lossy()
+            .to_string(),
+        "/srv/values #1.json".to_string(),
+        "C:\\values\\apple.json".to_string(),
+    ]
+    .into_iter()
+    .map(|source| {
+        let outcome = ValidationOutcome::new::<()>(
+            source,
+            None,
+            Err(Error::new(ErrorKind::InvalidValue, "invalid")),
+        );
+
+        to_sarif("cooplan-validate", &[outcome])["runs"][0]["results"][0]["locations"][0]
+            ["physicalLocation"]["artifactLocation"]["uri"]
+            .take()
+    })
+    .collect();
+
+    assert_eq!(
+        vec![
+            "values/new%20batch.ndjson",
+            "values/a%3Ab.json",
+            "values.json",
+            "file:///srv/values%20%231.json",
+            "file:///C:/values/apple.json",
+        ],
+        uris
+    );
+
+    let outcome = ValidationOutcome::new::<()>(
+        STDIN_SOURCE,
+        Some(3),
+        Err(Error::new(ErrorKind::InvalidValue, "invalid")),
+    );
+    let sarif = to_sarif("cooplan-validate", &[outcome]);
+
+    assert!(sarif["runs"][0]["results"][0].get("locations").is_none());
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+use serde_json::Value;
+
+use cooplan_definition_schema_validator::{
+    definition_type::DefinitionType,
+    error::ErrorKind,
+    sample::{SampleGenerator, SampleRng},
+    schema_validator::SchemaValidator,
+    validations::validate_string,
+};
+
+use common::{
+    build_attribute, build_category, build_named_attribute, build_product_definition, IS_SERVICE_ID,
+};
+
+fn build_definition() -> Definition {
+    let mut categories = build_product_definition(
+        vec![
+            build_attribute("10", "string", false),
+            build_attribute("11", "integer", false),
+            build_attribute("12", "decimal", true),
+        ],
+        vec![build_attribute("13", "boolean", false)],
+    )
+    .categories();
+
+    categories.push(build_category(
+        "3",
+        None,
+        true,
+        vec![
+            build_attribute("20", "color", false),
+            build_named_attribute(IS_SERVICE_ID, "IS_SERVICE", "boolean", true),
+        ],
+    ));
+
+    Definition::new("1".to_string(), categories)
+}
+
+#[test]
+fn generated_values_pass_validation() {
+    let sample_generator = SampleGenerator::default();
+    let mut schema_validator = SchemaValidator::default();
+    let mut rng = SampleRng::seed_from_u64(42);
+
+    for _ in 0..100 {
+        let value = sample_generator
+            .generate(&build_definition(), &"2".to_string(), &mut rng)
+            .expect("failed to generate fruit");
+
+        assert_eq!(Some(&Value::String("1".to_string())), value.get("version"));
+        assert_eq!(Some(&Value::String("2".to_string())), value.get("type"));
+
+        let definition_value = schema_validator
+            .validate_object(value, build_definition())
+            .expect("generated fruit is invalid");
+        assert_eq!(&DefinitionType::Product, definition_value.definition_type());
+    }
+}
+
+#[test]
+fn same_seed_generates_same_values() {
+    let sample_generator = SampleGenerator::default();
+
+    let generate = |seed: u64| {
+        let mut rng = SampleRng::seed_from_u64(seed);
+
+        (0..10)
+            .map(|_| {
+                sample_generator
+                    .generate(&build_definition(), &"2".to_string(), &mut rng)
+                    .expect("failed to generate fruit")
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(generate(7), generate(7));
+    assert_ne!(generate(7), generate(8));
+}
+
+#[test]
+fn optional_attributes_are_sometimes_omitted() {
+    let sample_generator = SampleGenerator::default();
+    let mut rng = SampleRng::seed_from_u64(1);
+
+    let values: Vec<_> = (0..50)
+        .map(|_| {
+            sample_generator
+                .generate(&build_definition(), &"2".to_string(), &mut rng)
+                .expect("failed to generate fruit")
+        })
+        .collect();
+
+    assert!(values.iter().any(|value| value.contains_key("12")));
+    assert!(values.iter().any(|value| !value.contains_key("12")));
+    assert!(values.iter().all(|value| value.contains_key("11")));
+}
+
+#[test]
+fn custom_data_type_requires_a_generator() {
+    let mut sample_generator = SampleGenerator::default();
+    let mut rng = SampleRng::seed_from_u64(0);
+
+    let error = sample_generator
+        .generate(&build_definition(), &"3".to_string(), &mut rng)
+        .unwrap_err();
+    assert_eq!(ErrorKind::DataTypeNotRegistered, error.kind());
+    assert_eq!(Some("20".to_string()), error.attribute_id);
+
+    sample_generator.register_generator(
+        "color".to_string(),
+        Box::new(|rng: &mut SampleRng| {
+            Value::String(["red", "green", "blue"][rng.below(3) as usize].to_string())
+        }),
+    );
+
+    let value = sample_generator
+        .generate(&build_definition(), &"3".to_string(), &mut rng)
+        .expect("failed to generate delivery");
+    assert_eq!(Some(&Value::Bool(true)), value.get(IS_SERVICE_ID));
+
+    let mut schema_validator = SchemaValidator::default();
+    schema_validator.register_validation("color".to_string(), Box::new(validate_string));
+
+    let definition_value = schema_validator
+        .validate_object(value, build_definition())
+        .expect("generated delivery is invalid");
+    assert_eq!(&DefinitionType::Service, definition_value.definition_type());
+}
+
+#[test]
+fn non_selectable_category_is_rejected() {
+    assert_eq!(
+        ErrorKind::InvalidArgument,
+        SampleGenerator::default()
+            .generate(
+                &build_definition(),
+                &"1".to_string(),
+                &mut SampleRng::seed_from_u64(0)
+            )
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[test]
+fn category_without_definition_type_marker_is_rejected() {
+    let mut categories = build_definition().categories();
+    categories.push(build_category(
+        "4",
+        None,
+        true,
+        vec![build_attribute("40", "string", false)],
+    ));
+    let definition = Definition::new("1".to_string(), categories);
+
+    assert_eq!(
+        ErrorKind::InvalidDefinition,
+        SampleGenerator::default()
+            .generate(
+                &definition,
+                &"4".to_string(),
+                &mut SampleRng::seed_from_u64(0)
+            )
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[cfg(feature = "proptest")]
+mod property {
+    use std::sync::Arc;
+
+    use proptest::prelude::*;
+
+    use cooplan_definition_schema_validator::{
+        sample::{strategy, SampleGenerator},
+        schema_validator::SchemaValidator,
+    };
+
+    use super::build_definition;
+
+    proptest! {
+        #[test]
+        fn generated_fruits_pass_validation(
+            value in strategy(Arc::new(SampleGenerator::default()), build_definition(), "2".to_string())
+                .expect("failed to build strategy")
+        ) {
+            prop_assert!(SchemaValidator::default()
+                .validate_object(value, build_definition())
+                .is_ok());
+        }
+    }
+}
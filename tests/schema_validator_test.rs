@@ -1,3 +1,7 @@
+#![allow(non_snake_case)]
+
+mod common;
+
 use serde_json::{Map, Number, Value};
 
 use cooplan_definition_schema_validator::error::ErrorKind;
@@ -317,3 +321,48 @@ fn object_validity_test_case_1() {
 
     assert!(!definition_value_object.contains_key("extra"));
 }
+
+#[test]
+fn optional_attributes_may_be_omitted_but_are_validated_when_present() {
+    use cooplan_definition_schema_validator::schema_validator::SchemaValidator;
+
+    use common::{build_attribute, build_product_definition};
+
+    let definition = build_product_definition(
+        vec![build_attribute("11", "integer", false)],
+        vec![build_attribute("12", "decimal", true)],
+    );
+
+    let mut schema_validator = SchemaValidator::default();
+
+    let definition_value = schema_validator
+        .validate(
+            String::from(
+                "{ \"type\": \"2\", \"version\": \"1\", \"11\": 600, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+            ),
+            definition.clone(),
+        )
+        .expect("failed to validate when an optional attribute is omitted");
+    assert!(!definition_value.value().contains_key("12"));
+
+    let error = schema_validator
+        .validate(
+            String::from(
+                "{ \"type\": \"2\", \"version\": \"1\", \"11\": 600, \"12\": \"heavy\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+            ),
+            definition.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(ErrorKind::InvalidValue, error.kind());
+
+    let error = schema_validator
+        .validate(
+            String::from(
+                "{ \"type\": \"2\", \"version\": \"1\", \"12\": 0.2, \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }",
+            ),
+            definition,
+        )
+        .unwrap_err();
+    assert_eq!(ErrorKind::InvalidValue, error.kind());
+    assert!(error.message.contains("'11'"));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    definition_registry::DefinitionRegistry,
+    error::ErrorKind,
+    schema_validator::SchemaValidator,
+    source_map::{member_pointer, SourceMap, SourceSpan},
+};
+
+use common::{build_named_attribute, build_product_definition};
+
+const VALUE: &str = "{\n  \"type\": \"2\",\n  \"version\": \"1\",\n  \"11\": \"many\",\n  \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true\n}";
+
+fn build_definition() -> Definition {
+    build_product_definition(
+        vec![build_named_attribute("11", "count", "integer", false)],
+        vec![],
+    )
+}
+
+fn line_and_columns(span: SourceSpan) -> (usize, usize, usize, usize) {
+    (
+        span.start.line,
+        span.start.column,
+        span.end.line,
+        span.end.column,
+    )
+}
+
+#[test]
+fn spans_of_keys_and_values_are_mapped() {
+    let source_map = SourceMap::parse(
+        "{ \"name\": \"Äpfel\", \"tags\": [1, { \"a/b\": null }],\n  \"~\": -1.5e3 }",
+    )
+    .expect("failed to map source");
+
+    assert_eq!(
+        (1, 1, 2, 16),
+        line_and_columns(source_map.root().expect("root is not mapped"))
+    );
+    assert_eq!(
+        (1, 3, 1, 9),
+        line_and_columns(source_map.key("/name").expect("key is not mapped"))
+    );
+    assert_eq!(
+        (1, 11, 1, 18),
+        line_and_columns(source_map.value("/name").expect("value is not mapped"))
+    );
+    assert_eq!(
+        (1, 41, 1, 45),
+        line_and_columns(
+            source_map
+                .value(&member_pointer("/tags/1", "a/b"))
+                .expect("nested value is not mapped")
+        )
+    );
+    assert_eq!(
+        (2, 8, 2, 14),
+        line_and_columns(source_map.value("/~0").expect("value is not mapped"))
+    );
+
+    let error = SourceMap::parse("{ \"name\": }").expect_err("malformed source was mapped");
+    assert_eq!(ErrorKind::DeserializationFailure, error.kind());
+    assert_eq!(Some(11), error.span.map(|span| span.start.column));
+}
+
+#[test]
+fn validation_errors_carry_the_span_of_the_offending_token() {
+    let mut schema_validator = SchemaValidator::default();
+
+    let error = schema_validator
+        .validate(VALUE.to_string(), build_definition())
+        .expect_err("invalid value was validated");
+    assert_eq!(ErrorKind::InvalidValue, error.kind());
+    assert_eq!(Some((4, 9, 4, 15)), error.span.map(line_and_columns));
+
+    let error = schema_validator
+        .validate(VALUE.replace("\"2\"", "\"9\""), build_definition())
+        .expect_err("value of unknown category was validated");
+    assert_eq!(Some((2, 11, 2, 14)), error.span.map(line_and_columns));
+
+    let error = schema_validator
+        .validate(VALUE.replace("\"11\": \"many\",", ""), build_definition())
+        .expect_err("value missing an attribute was validated");
+    assert_eq!(Some("11".to_string()), error.attribute_id);
+    assert_eq!(Some((1, 1, 6, 2)), error.span.map(line_and_columns));
+
+    let error = schema_validator
+        .validate(VALUE.replace("true", "true,"), build_definition())
+        .expect_err("malformed value was validated");
+    assert_eq!(ErrorKind::DeserializationFailure, error.kind());
+    assert_eq!(Some(6), error.span.map(|span| span.start.line));
+
+    let object = serde_json::from_str(VALUE).expect("failed to deserialize value");
+    let error = schema_validator
+        .validate_object(object, build_definition())
+        .expect_err("invalid object was validated");
+    assert!(error.span.is_none());
+}
+
+#[test]
+fn registry_validation_errors_carry_the_span_of_the_offending_token() {
+    let mut definition_registry = DefinitionRegistry::default();
+    definition_registry.register_definition(build_definition());
+
+    let error = definition_registry
+        .validate(
+            &mut SchemaValidator::default(),
+            VALUE.replace("\"version\": \"1\"", "\"version\": \"7\""),
+        )
+        .expect_err("value of unknown version was validated");
+    assert_eq!(ErrorKind::UnknownDefinitionVersion, error.kind());
+    assert_eq!(Some((3, 14, 3, 17)), error.span.map(line_and_columns));
+}
@@ -107,6 +107,7 @@ fn detects_decimal_correctly() {
 }
 
 #[test]
+#[allow(clippy::zero_prefixed_literal)]
 fn decimal_contains_number_set() {
     use cooplan_definition_schema_validator::validations::validate_decimal;
     use serde_json::{Number, Value};
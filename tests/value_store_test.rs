@@ -0,0 +1,156 @@
+mod common;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    definition_type::DefinitionType, definition_value::DefinitionValue,
+    schema_validator::SchemaValidator, value_store::ValueStore,
+};
+
+use common::{build_category, build_named_attribute, build_product_definition, IS_SERVICE_ID};
+
+fn build_definition() -> Definition {
+    let name_attribute = build_named_attribute("10", "name", "string", false);
+
+    let mut categories =
+        build_product_definition(vec![name_attribute.clone()], vec![]).categories();
+    categories.push(build_category("3", Some("1"), true, vec![]));
+    categories.push(build_category(
+        "4",
+        None,
+        true,
+        vec![
+            name_attribute,
+            build_named_attribute(IS_SERVICE_ID, "IS_SERVICE", "boolean", false),
+        ],
+    ));
+
+    Definition::new("1".to_string(), categories)
+}
+
+fn validate_product(category: &str, name: &str) -> DefinitionValue {
+    SchemaValidator::default()
+        .validate(
+            format!(
+                "{{ \"type\": \"{}\", \"version\": \"1\", \"10\": \"{}\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }}",
+                category, name
+            ),
+            build_definition(),
+        )
+        .expect("failed to validate product")
+}
+
+fn validate_delivery(name: &str) -> DefinitionValue {
+    SchemaValidator::default()
+        .validate(
+            format!(
+                "{{ \"type\": \"4\", \"version\": \"1\", \"10\": \"{}\", \"70b0d023-20e6-45cb-9654-e5fd42749642\": true }}",
+                name
+            ),
+            build_definition(),
+        )
+        .expect("failed to validate delivery")
+}
+
+fn build_store() -> ValueStore<String> {
+    let mut value_store = ValueStore::new();
+
+    value_store.insert("pear".to_string(), validate_product("2", "Pear"));
+    value_store.insert("apple".to_string(), validate_product("2", "Apple"));
+    value_store.insert("carrot".to_string(), validate_product("3", "Carrot"));
+    value_store.insert("express".to_string(), validate_delivery("Express"));
+
+    value_store
+}
+
+fn sorted_keys<'a>(
+    values: impl Iterator<Item = (&'a String, &'a DefinitionValue)>,
+) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = values.map(|(key, _)| key.as_str()).collect();
+    keys.sort();
+
+    keys
+}
+
+#[test]
+fn category_query_includes_descendants() {
+    let value_store = build_store();
+
+    assert_eq!(
+        vec!["apple", "carrot", "pear"],
+        sorted_keys(value_store.in_category("1"))
+    );
+    assert_eq!(
+        vec!["apple", "pear"],
+        sorted_keys(value_store.in_category("2"))
+    );
+    assert_eq!(vec!["express"], sorted_keys(value_store.in_category("4")));
+    assert!(sorted_keys(value_store.in_category("5")).is_empty());
+}
+
+#[test]
+fn definition_type_query_finds_values() {
+    let value_store = build_store();
+
+    assert_eq!(
+        vec!["express"],
+        sorted_keys(value_store.of_definition_type(&DefinitionType::Service))
+    );
+    assert_eq!(
+        3,
+        value_store
+            .of_definition_type(&DefinitionType::Product)
+            .count()
+    );
+    assert_eq!(
+        0,
+        value_store
+            .of_definition_type(&DefinitionType::Modifier)
+            .count()
+    );
+}
+
+#[test]
+fn removed_value_is_unindexed() {
+    let mut value_store = build_store();
+
+    let removed_value = value_store
+        .remove(&"carrot".to_string())
+        .expect("failed to remove carrot");
+
+    assert_eq!(
+        Some(&"3".to_string()),
+        removed_value.category_chain().leaf()
+    );
+    assert_eq!(3, value_store.len());
+    assert!(!value_store.contains_key(&"carrot".to_string()));
+    assert!(sorted_keys(value_store.in_category("3")).is_empty());
+    assert_eq!(
+        vec!["apple", "pear"],
+        sorted_keys(value_store.in_category("1"))
+    );
+    assert!(value_store.remove(&"carrot".to_string()).is_none());
+}
+
+#[test]
+fn replaced_value_is_reindexed() {
+    let mut value_store = build_store();
+
+    let replaced_value = value_store
+        .insert("pear".to_string(), validate_delivery("Pear delivery"))
+        .expect("failed to replace pear");
+
+    assert_eq!(&DefinitionType::Product, replaced_value.definition_type());
+    assert_eq!(4, value_store.len());
+    assert_eq!(vec!["apple"], sorted_keys(value_store.in_category("2")));
+    assert_eq!(
+        vec!["express", "pear"],
+        sorted_keys(value_store.of_definition_type(&DefinitionType::Service))
+    );
+    assert_eq!(
+        2,
+        value_store
+            .of_definition_type(&DefinitionType::Product)
+            .count()
+    );
+}
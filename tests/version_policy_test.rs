@@ -0,0 +1,94 @@
+mod common;
+
+use std::collections::HashMap;
+
+use cooplan_definitions_lib::definition::Definition;
+
+use cooplan_definition_schema_validator::{
+    error::ErrorKind,
+    schema_validator::SchemaValidator,
+    version_policy::{VersionPolicy, VersionPolicyKind},
+};
+
+use common::{build_category, build_is_product_attribute};
+
+fn build_definition(version: &str) -> Definition {
+    Definition::new(
+        version.to_string(),
+        vec![build_category(
+            "1",
+            None,
+            true,
+            vec![build_is_product_attribute()],
+        )],
+    )
+}
+
+fn build_value(version: &str) -> String {
+    format!(
+        "{{ \"type\": \"1\", \"version\": \"{}\", \"4ed908eb-50b6-4faa-9baa-a7a897cec30f\": true }}",
+        version
+    )
+}
+
+#[test]
+fn exact_policy_ignores_case_only() {
+    let version_policy = VersionPolicy::Exact;
+
+    assert!(version_policy.is_compatible("1.0.0-RC", "1.0.0-rc"));
+    assert!(!version_policy.is_compatible("1.0.1", "1.0.0"));
+}
+
+#[test]
+fn semver_compatible_policy_accepts_older_minor_versions() {
+    let version_policy = VersionPolicy::SemverCompatible;
+
+    assert!(version_policy.is_compatible("1.2.3", "1.2.0"));
+    assert!(version_policy.is_compatible("1.2.3", "1.1.9"));
+    assert!(version_policy.is_compatible("v1.2", "1"));
+    assert!(version_policy.is_compatible("1.2.0", "1.2.5"));
+    assert!(!version_policy.is_compatible("1.2.3", "1.3.0"));
+    assert!(!version_policy.is_compatible("2.0.0", "1.0.0"));
+    assert!(!version_policy.is_compatible("1.2.3", "latest"));
+}
+
+#[test]
+fn compatibility_table_policy_accepts_listed_versions() {
+    let mut compatibility_table: HashMap<String, Vec<String>> = HashMap::new();
+    compatibility_table.insert(
+        "2023-B".to_string(),
+        vec!["2023-a".to_string(), "2022-C".to_string()],
+    );
+
+    let version_policy = VersionPolicy::CompatibilityTable(compatibility_table);
+
+    assert!(version_policy.is_compatible("2023-b", "2023-A"));
+    assert!(version_policy.is_compatible("2023-B", "2022-c"));
+    assert!(version_policy.is_compatible("2023-B", "2023-B"));
+    assert!(!version_policy.is_compatible("2023-A", "2023-B"));
+}
+
+#[test]
+fn validation_records_applied_version_policy() {
+    let mut schema_validator = SchemaValidator::default();
+
+    assert_eq!(
+        ErrorKind::ValueDefinitionMismatch,
+        schema_validator
+            .validate(build_value("1.0.0"), build_definition("1.0.1"))
+            .unwrap_err()
+            .kind()
+    );
+
+    schema_validator.set_version_policy(VersionPolicy::SemverCompatible);
+
+    let definition_value = schema_validator
+        .validate(build_value("1.0.0"), build_definition("1.0.1"))
+        .expect("failed to validate value of a compatible version");
+
+    assert_eq!("1.0.1", definition_value.definition());
+    assert_eq!(
+        VersionPolicyKind::SemverCompatible,
+        definition_value.version_policy()
+    );
+}